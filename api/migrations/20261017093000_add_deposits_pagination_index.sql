@@ -0,0 +1,7 @@
+-- Composite indexes backing keyset pagination on (created_at, deposit_id)
+CREATE INDEX IF NOT EXISTS idx_deposits_created_at_deposit_id
+    ON deposits(created_at DESC, deposit_id DESC);
+CREATE INDEX IF NOT EXISTS idx_deposits_user_address_created_at_deposit_id
+    ON deposits(user_address, created_at DESC, deposit_id DESC);
+CREATE INDEX IF NOT EXISTS idx_deposits_status_created_at_deposit_id
+    ON deposits(status, created_at DESC, deposit_id DESC);
@@ -0,0 +1,26 @@
+-- Publish status, transaction hash and swap id changes on the deposit_changes channel
+CREATE OR REPLACE FUNCTION notify_deposit_change() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify(
+        'deposit_changes',
+        json_build_object(
+            'deposit_id', NEW.deposit_id,
+            'status', NEW.status,
+            'deposit_tx_hash', NEW.deposit_tx_hash,
+            'atomiq_swap_id', NEW.atomiq_swap_id
+        )::text
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS deposits_notify_change ON deposits;
+CREATE TRIGGER deposits_notify_change
+    AFTER UPDATE ON deposits
+    FOR EACH ROW
+    WHEN (
+        OLD.status IS DISTINCT FROM NEW.status
+        OR OLD.deposit_tx_hash IS DISTINCT FROM NEW.deposit_tx_hash
+        OR OLD.atomiq_swap_id IS DISTINCT FROM NEW.atomiq_swap_id
+    )
+    EXECUTE FUNCTION notify_deposit_change();
@@ -0,0 +1,59 @@
+-- Webhook subscriptions registered by partners
+CREATE TABLE IF NOT EXISTS webhook_subscriptions (
+    id TEXT PRIMARY KEY,
+    partner TEXT NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT[] NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_subscriptions_partner ON webhook_subscriptions(partner);
+
+-- Durable delivery queue, one row per (subscription, deposit transition)
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    delivery_id BIGSERIAL PRIMARY KEY,
+    subscription_id TEXT NOT NULL REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+    event TEXT NOT NULL,
+    deposit_id TEXT NOT NULL REFERENCES deposits(deposit_id),
+    payload JSONB,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'delivered', 'dead')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    last_status_code INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due
+    ON webhook_deliveries(next_attempt_at)
+    WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_subscription_id
+    ON webhook_deliveries(subscription_id, delivery_id DESC);
+
+-- Enqueue a delivery for every active subscription interested in a deposit transition.
+-- Events are named "deposit.<status>", e.g. "deposit.created" or "deposit.deposited".
+CREATE OR REPLACE FUNCTION enqueue_webhook_deliveries() RETURNS trigger AS $$
+DECLARE
+    event_name TEXT := 'deposit.' || NEW.status;
+BEGIN
+    IF TG_OP = 'UPDATE' AND OLD.status IS NOT DISTINCT FROM NEW.status THEN
+        RETURN NEW;
+    END IF;
+
+    INSERT INTO webhook_deliveries (subscription_id, event, deposit_id)
+    SELECT id, event_name, NEW.deposit_id
+    FROM webhook_subscriptions
+    WHERE active AND event_name = ANY(events);
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS deposits_enqueue_webhook_deliveries ON deposits;
+CREATE TRIGGER deposits_enqueue_webhook_deliveries
+    AFTER INSERT OR UPDATE OF status ON deposits
+    FOR EACH ROW
+    EXECUTE FUNCTION enqueue_webhook_deliveries();
@@ -0,0 +1,10 @@
+-- Idempotency keys of POST /deposit, scoped per user so keys cannot collide across users
+CREATE TABLE IF NOT EXISTS deposit_idempotency_keys (
+    user_address TEXT NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    -- SHA-256 of the normalised request, used to detect key reuse with a different body
+    request_hash TEXT NOT NULL,
+    deposit_id TEXT NOT NULL REFERENCES deposits(deposit_id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_address, idempotency_key)
+);
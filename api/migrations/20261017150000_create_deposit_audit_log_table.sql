@@ -0,0 +1,17 @@
+-- Audit trail of administrative actions taken on deposits through the admin API
+CREATE TABLE IF NOT EXISTS deposit_audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    deposit_id TEXT NOT NULL REFERENCES deposits(deposit_id) ON DELETE CASCADE,
+    -- Name of the operator whose admin token authorised the action
+    operator TEXT NOT NULL,
+    action TEXT NOT NULL CHECK (action IN ('force_status', 'requeue', 'recover')),
+    from_status TEXT,
+    to_status TEXT,
+    reason TEXT NOT NULL,
+    -- Transaction sent on-chain by the action, e.g. the recover() invocation
+    tx_hash TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_deposit_audit_log_deposit_id
+    ON deposit_audit_log(deposit_id, id DESC);
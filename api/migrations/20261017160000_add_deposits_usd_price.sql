@@ -0,0 +1,3 @@
+-- USD price of one token unit when the deposit was created, used to value exports.
+-- Deposits created before this column existed have no price.
+ALTER TABLE deposits ADD COLUMN IF NOT EXISTS usd_price NUMERIC;
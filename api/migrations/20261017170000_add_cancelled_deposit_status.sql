@@ -0,0 +1,4 @@
+-- Deposits abandoned by their owner before being funded
+ALTER TABLE deposits DROP CONSTRAINT IF EXISTS deposits_status_check;
+ALTER TABLE deposits ADD CONSTRAINT deposits_status_check
+    CHECK (status IN ('created', 'initiated', 'deposited', 'cancelled'));
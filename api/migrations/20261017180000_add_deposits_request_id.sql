@@ -0,0 +1,3 @@
+-- ID of the API request that created the deposit, so that the vault watcher's
+-- logs can be correlated with the API's. Older deposits have no request ID.
+ALTER TABLE deposits ADD COLUMN IF NOT EXISTS request_id TEXT;
@@ -0,0 +1,33 @@
+-- Deposits sharing an address must be resolved by hand before
+-- 20261017190000_add_deposits_deposit_address_unique can add its constraint, as
+-- only an operator can tell which one is genuine. This check is versioned
+-- before that migration so that it runs first on databases that have not
+-- applied it yet; on databases that have, the constraint already holds and the
+-- check finds nothing.
+DO $$
+DECLARE
+    duplicates TEXT;
+BEGIN
+    -- Addresses are compared as canonicalised by the next migration
+    SELECT string_agg(format('%s (%s)', canonical_address, deposit_ids), '; ')
+    INTO duplicates
+    FROM (
+        SELECT
+            '0x' || COALESCE(
+                NULLIF(ltrim(lower(regexp_replace(deposit_address, '^0[xX]', '')), '0'), ''),
+                '0'
+            ) AS canonical_address,
+            string_agg(deposit_id, ', ' ORDER BY created_at) AS deposit_ids
+        FROM deposits
+        GROUP BY 1
+        HAVING COUNT(*) > 1
+        ORDER BY 1
+        LIMIT 20
+    ) d;
+
+    IF duplicates IS NOT NULL THEN
+        RAISE EXCEPTION 'Deposits share a deposit address, resolve them before adding deposits_deposit_address_key: %', duplicates
+            USING HINT = 'Delete all but one deposit of each address listed (at most 20 are shown), then restart the API to run the migrations again';
+    END IF;
+END
+$$;
@@ -0,0 +1,12 @@
+-- Canonicalise deposit addresses (lowercase, 0x prefix, no leading zeros) as
+-- written by the API, so that lookups by address can use plain equality
+UPDATE deposits
+SET deposit_address = '0x' || COALESCE(
+    NULLIF(ltrim(lower(regexp_replace(deposit_address, '^0[xX]', '')), '0'), ''),
+    '0'
+);
+
+-- Two deposits can never share an address; the constraint's index also backs
+-- lookups by address
+ALTER TABLE deposits
+    ADD CONSTRAINT deposits_deposit_address_key UNIQUE (deposit_address);
@@ -0,0 +1,12 @@
+-- Idempotency keys of POST /deposits/batch, scoped per signer and set of users of the batch
+CREATE TABLE IF NOT EXISTS deposit_batch_idempotency_keys (
+    -- SHA-256 of the signer and the sorted, normalised user addresses of the batch
+    scope TEXT NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    -- SHA-256 of the normalised request, used to detect key reuse with a different body
+    request_hash TEXT NOT NULL,
+    -- One entry per requested deposit, in request order: the created deposit ID or the rejection
+    outcomes JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (scope, idempotency_key)
+);
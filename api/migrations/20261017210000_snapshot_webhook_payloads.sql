@@ -0,0 +1,64 @@
+-- Snapshot webhook payloads when a delivery is enqueued, so that an event carries
+-- the deposit as it was at the transition and every attempt sends the same body.
+
+-- The deposit as serialised in API responses
+CREATE OR REPLACE FUNCTION webhook_deposit_data(d deposits) RETURNS jsonb AS $$
+    SELECT jsonb_build_object(
+        'deposit_id', d.deposit_id,
+        'user_address', d.user_address,
+        'action', d.action,
+        'amount', d.amount::TEXT,
+        'token', d.token,
+        'target_address', d.target_address,
+        'deposit_address', d.deposit_address,
+        'status', d.status,
+        'created_at', to_char(d.created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"Z"'),
+        'deposit_tx_hash', d.deposit_tx_hash,
+        'atomiq_swap_id', d.atomiq_swap_id
+    );
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION enqueue_webhook_deliveries() RETURNS trigger AS $$
+DECLARE
+    event_name TEXT := 'deposit.' || NEW.status;
+BEGIN
+    IF TG_OP = 'UPDATE' AND OLD.status IS NOT DISTINCT FROM NEW.status THEN
+        RETURN NEW;
+    END IF;
+
+    -- The delivery ID is drawn up front because it is part of the payload
+    INSERT INTO webhook_deliveries (delivery_id, subscription_id, event, deposit_id, payload)
+    SELECT
+        s.delivery_id,
+        s.id,
+        event_name,
+        NEW.deposit_id,
+        jsonb_build_object(
+            'id', s.delivery_id,
+            'event', event_name,
+            'created_at', to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"Z"'),
+            'data', webhook_deposit_data(NEW)
+        )
+    FROM (
+        SELECT nextval(pg_get_serial_sequence('webhook_deliveries', 'delivery_id')) AS delivery_id, id
+        FROM webhook_subscriptions
+        WHERE active AND event_name = ANY(events)
+    ) s;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Deliveries enqueued before this migration have no payload yet; the current
+-- deposit row is the best snapshot left for them
+UPDATE webhook_deliveries w
+SET payload = jsonb_build_object(
+    'id', w.delivery_id,
+    'event', w.event,
+    'created_at', to_char(w.created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"Z"'),
+    'data', webhook_deposit_data(d)
+)
+FROM deposits d
+WHERE w.payload IS NULL AND d.deposit_id = w.deposit_id;
+
+ALTER TABLE webhook_deliveries ALTER COLUMN payload SET NOT NULL;
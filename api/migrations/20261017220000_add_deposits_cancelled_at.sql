@@ -0,0 +1,15 @@
+-- When a deposit was cancelled, and when the vault watcher reported funds that
+-- arrived at its address afterwards
+ALTER TABLE deposits
+    ADD COLUMN IF NOT EXISTS cancelled_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS cancelled_funds_alerted_at TIMESTAMPTZ;
+
+-- The cancellation time of existing deposits is unknown; watch them from now on
+UPDATE deposits
+SET cancelled_at = NOW()
+WHERE status = 'cancelled' AND cancelled_at IS NULL;
+
+-- Backs the vault watcher's scan of recently cancelled deposits not yet reported
+CREATE INDEX IF NOT EXISTS idx_deposits_cancelled_at_unalerted
+    ON deposits(cancelled_at)
+    WHERE status = 'cancelled' AND cancelled_funds_alerted_at IS NULL;
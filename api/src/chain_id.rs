@@ -0,0 +1,80 @@
+use std::time::Instant;
+
+use starknet::{
+    core::types::Felt,
+    providers::{JsonRpcClient, Provider, ProviderError, jsonrpc::HttpTransport},
+};
+use tokio::sync::OnceCell;
+use tracing::Instrument;
+
+use crate::metrics::{METRICS, UPSTREAM_STARKNET_RPC, upstream_span};
+
+/// Chain ID of the Starknet network the API runs against
+///
+/// The chain ID is only needed to verify SNIP-12 signatures, sign recovery
+/// transactions and check readiness, so it is read from the RPC on first use
+/// rather than at startup: the API starts while the RPC is unreachable, and
+/// those features fail until the RPC answers once. `GET /ready` reports the
+/// outage in the meantime.
+pub struct ChainId {
+    provider: JsonRpcClient<HttpTransport>,
+    chain_id: OnceCell<Felt>,
+}
+
+impl ChainId {
+    /// Creates a new ChainId instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - Starknet RPC provider asked for the chain ID
+    /// * `configured` - Chain ID from settings; the RPC is never asked when set
+    pub fn new(provider: JsonRpcClient<HttpTransport>, configured: Option<Felt>) -> Self {
+        Self {
+            provider,
+            chain_id: OnceCell::new_with(configured),
+        }
+    }
+
+    /// Returns the chain ID, reading it from the RPC until a read succeeds
+    pub async fn get(&self) -> Result<Felt, ProviderError> {
+        self.chain_id
+            .get_or_try_init(|| async {
+                let started = Instant::now();
+                let result = self
+                    .provider
+                    .chain_id()
+                    .instrument(upstream_span(UPSTREAM_STARKNET_RPC, "chain_id"))
+                    .await;
+                let outcome = if result.is_ok() { "ok" } else { "error" };
+                METRICS.observe_upstream(UPSTREAM_STARKNET_RPC, "chain_id", outcome, started);
+                result
+            })
+            .await
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+
+    use super::*;
+
+    fn unreachable_provider() -> JsonRpcClient<HttpTransport> {
+        JsonRpcClient::new(HttpTransport::new(
+            Url::parse("http://127.0.0.1:1").unwrap(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_chain_id_survives_an_unreachable_rpc() {
+        let configured = Felt::from_hex_unchecked("0x534e5f4d41494e");
+        let chain_id = ChainId::new(unreachable_provider(), Some(configured));
+        assert_eq!(chain_id.get().await.unwrap(), configured);
+
+        let chain_id = ChainId::new(unreachable_provider(), None);
+        assert!(chain_id.get().await.is_err());
+        // Not cached, so the next use asks the RPC again
+        assert!(chain_id.chain_id.get().is_none());
+    }
+}
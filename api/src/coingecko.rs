@@ -1,10 +1,20 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::RwLock,
+    time::{Duration, Instant},
+};
 
 use bigdecimal::BigDecimal;
 use eyre::Result;
 use moka::future::Cache;
 use reqwest::{Client, Url};
-use tracing::{error, info};
+use tokio::sync::watch;
+use tracing::{Instrument, error, info};
+
+use crate::{
+    metrics::{METRICS, UPSTREAM_COINGECKO, upstream_span},
+    shutdown,
+};
 
 /// HTTP request timeout for Coingecko API calls in seconds
 const CG_REQUEST_TIMEOUT_SECS: u64 = 10;
@@ -56,6 +66,7 @@ pub struct CoingeckoFiatProvider {
     api_key: String,
     price_update_interval_secs: u64,
     cache: Cache<String, BigDecimal>,
+    updated_at: RwLock<HashMap<String, chrono::DateTime<chrono::Utc>>>,
 }
 
 impl CoingeckoFiatProvider {
@@ -105,6 +116,7 @@ impl CoingeckoFiatProvider {
             price_update_interval_secs: update_interval_secs
                 .unwrap_or(CG_PRICE_UPDATE_INTERVAL_SECS),
             cache,
+            updated_at: RwLock::new(HashMap::new()),
         }
     }
 
@@ -117,15 +129,20 @@ impl CoingeckoFiatProvider {
     /// # Note
     ///
     /// This is a blocking method that should typically be spawned in a separate
-    /// task using `tokio::spawn`.
-    pub async fn start(&self) {
+    /// task using `tokio::spawn`. It returns once shutdown is requested on
+    /// `shutdown`.
+    pub async fn start(&self, mut shutdown: watch::Receiver<bool>) {
         info!("Starting Coingecko fiat provider");
         loop {
             if let Err(e) = self.update_prices().await {
                 error!("Failed to update prices: {}", e);
             };
-            tokio::time::sleep(Duration::from_secs(self.price_update_interval_secs)).await;
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(self.price_update_interval_secs)) => {}
+                _ = shutdown::requested(&mut shutdown) => break,
+            }
         }
+        info!("Stopped Coingecko fiat provider");
     }
 
     /// Updates prices for all configured assets.
@@ -154,7 +171,11 @@ impl CoingeckoFiatProvider {
                     continue;
                 }
             };
-            self.cache.insert(coin_id, usd_price.clone()).await;
+            self.cache.insert(coin_id.clone(), usd_price.clone()).await;
+            self.updated_at
+                .write()
+                .expect("price timestamps poisoned")
+                .insert(coin_id, chrono::Utc::now());
         }
         Ok(())
     }
@@ -189,9 +210,26 @@ impl CoingeckoFiatProvider {
             ])
             .header(CG_API_KEY_HEADER, self.api_key.clone());
 
-        let response = req.send().await?;
+        let started = Instant::now();
+        let response = match req
+            .send()
+            .instrument(upstream_span(UPSTREAM_COINGECKO, "price"))
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                METRICS.observe_upstream(UPSTREAM_COINGECKO, "price", "error", started);
+                return Err(e.into());
+            }
+        };
 
         let status = response.status();
+        let outcome = if status.is_success() {
+            "ok"
+        } else {
+            "http_error"
+        };
+        METRICS.observe_upstream(UPSTREAM_COINGECKO, "price", outcome, started);
 
         if !status.is_success() {
             let error_message = response.text().await.unwrap_or_default();
@@ -211,6 +249,17 @@ impl CoingeckoFiatProvider {
         Ok(prices)
     }
 
+    /// Returns when the price of each coin was last refreshed
+    ///
+    /// Unlike the price cache, entries do not expire, so stale prices keep
+    /// reporting their growing age.
+    pub fn price_updated_at(&self) -> HashMap<String, chrono::DateTime<chrono::Utc>> {
+        self.updated_at
+            .read()
+            .expect("price timestamps poisoned")
+            .clone()
+    }
+
     pub async fn get_all_prices(&self) -> Result<HashMap<String, BigDecimal>> {
         let prices = self
             .cache
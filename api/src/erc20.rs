@@ -0,0 +1,56 @@
+use std::time::Instant;
+
+use bigdecimal::BigDecimal;
+use starknet::{
+    core::{
+        types::{BlockId, BlockTag, Felt, FunctionCall},
+        utils::get_selector_from_name,
+    },
+    providers::{JsonRpcClient, Provider, jsonrpc::HttpTransport},
+};
+use tracing::Instrument;
+
+use crate::{
+    erc4626::u256_to_bigdecimal,
+    metrics::{METRICS, UPSTREAM_STARKNET_RPC, upstream_span},
+    registry::StarknetError,
+};
+
+/// Reads the token balance of an account
+///
+/// # Arguments
+/// * `provider` - Starknet RPC provider
+/// * `token` - The ERC-20 token contract address
+/// * `account` - The account whose balance is read
+///
+/// # Returns
+/// The balance in base units at the latest block
+pub async fn balance_of(
+    provider: &JsonRpcClient<HttpTransport>,
+    token: Felt,
+    account: Felt,
+) -> Result<BigDecimal, StarknetError> {
+    let call = FunctionCall {
+        contract_address: token,
+        entry_point_selector: get_selector_from_name("balance_of")
+            .map_err(|e| StarknetError::ContractCallFailed(format!("Invalid selector: {}", e)))?,
+        calldata: vec![account],
+    };
+
+    let started = Instant::now();
+    let result = provider
+        .call(call, BlockId::Tag(BlockTag::Latest))
+        .instrument(upstream_span(UPSTREAM_STARKNET_RPC, "balance_of"))
+        .await;
+    let outcome = if result.is_ok() { "ok" } else { "error" };
+    METRICS.observe_upstream(UPSTREAM_STARKNET_RPC, "balance_of", outcome, started);
+
+    let result = result.map_err(|e| StarknetError::ProviderError(e.to_string()))?;
+    match result.as_slice() {
+        [low, high] => Ok(u256_to_bigdecimal(low, high)),
+        _ => Err(StarknetError::InvalidResponse(format!(
+            "Expected a u256 from balance_of, got {} felts",
+            result.len()
+        ))),
+    }
+}
@@ -0,0 +1,83 @@
+use std::time::Instant;
+
+use bigdecimal::{
+    BigDecimal,
+    num_bigint::{BigInt, Sign},
+};
+use starknet::{
+    core::{
+        types::{BlockId, BlockTag, Felt, FunctionCall},
+        utils::get_selector_from_name,
+    },
+    providers::{JsonRpcClient, Provider, jsonrpc::HttpTransport},
+};
+use tracing::Instrument;
+
+use crate::{
+    metrics::{METRICS, UPSTREAM_STARKNET_RPC, upstream_span},
+    registry::{StarknetError, bigdecimal_to_i128s},
+};
+
+/// Simulates a deposit into an ERC-4626 vault such as a Vesu vToken
+///
+/// # Arguments
+/// * `provider` - Starknet RPC provider
+/// * `vault` - The vault contract address
+/// * `assets` - Amount of the underlying token in base units
+///
+/// # Returns
+/// The number of shares, in base units, the deposit would mint at the latest block
+pub async fn preview_deposit(
+    provider: &JsonRpcClient<HttpTransport>,
+    vault: Felt,
+    assets: &BigDecimal,
+) -> Result<BigDecimal, StarknetError> {
+    let (assets_low, assets_high) = bigdecimal_to_i128s(assets)?;
+    let call = FunctionCall {
+        contract_address: vault,
+        entry_point_selector: get_selector_from_name("preview_deposit")
+            .map_err(|e| StarknetError::ContractCallFailed(format!("Invalid selector: {}", e)))?,
+        calldata: vec![Felt::from(assets_low), Felt::from(assets_high)],
+    };
+
+    let started = Instant::now();
+    let result = provider
+        .call(call, BlockId::Tag(BlockTag::Latest))
+        .instrument(upstream_span(UPSTREAM_STARKNET_RPC, "preview_deposit"))
+        .await;
+    let outcome = if result.is_ok() { "ok" } else { "error" };
+    METRICS.observe_upstream(UPSTREAM_STARKNET_RPC, "preview_deposit", outcome, started);
+
+    let result = result.map_err(|e| StarknetError::ProviderError(e.to_string()))?;
+    match result.as_slice() {
+        [low, high] => Ok(u256_to_bigdecimal(low, high)),
+        _ => Err(StarknetError::InvalidResponse(format!(
+            "Expected a u256 from preview_deposit, got {} felts",
+            result.len()
+        ))),
+    }
+}
+
+/// Combines the low and high 128-bit halves of a u256
+pub(crate) fn u256_to_bigdecimal(low: &Felt, high: &Felt) -> BigDecimal {
+    let low = BigInt::from_bytes_be(Sign::Plus, &low.to_bytes_be());
+    let high = BigInt::from_bytes_be(Sign::Plus, &high.to_bytes_be());
+    BigDecimal::from((high << 128) + low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_u256_to_bigdecimal() {
+        assert_eq!(
+            u256_to_bigdecimal(&Felt::from(5u8), &Felt::ZERO).to_string(),
+            "5"
+        );
+        assert_eq!(
+            u256_to_bigdecimal(&Felt::ZERO, &Felt::ONE).to_string(),
+            "340282366920938463463374607431768211456"
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use bigdecimal::{
+    BigDecimal,
+    num_bigint::{BigInt, Sign},
+};
+use eyre::Result;
+use futures_util::future::join_all;
+use moka::future::Cache;
+use starknet::{
+    core::types::{Felt, PriceUnit, TransactionReceipt},
+    providers::{JsonRpcClient, Provider, jsonrpc::HttpTransport},
+};
+use tracing::{Instrument, warn};
+
+use crate::{
+    metrics::{METRICS, UPSTREAM_STARKNET_RPC, upstream_span},
+    orderbook::OrderbookProvider,
+    primitives::DeployFee,
+};
+
+/// Number of recent vault deployments averaged into the estimate
+const DEPLOY_FEE_SAMPLE_SIZE: i64 = 10;
+
+/// How long an estimate is reused, in seconds
+const DEPLOY_FEE_CACHE_TTL_SECS: u64 = 300;
+
+/// Number of decimals of both fee tokens (ETH and STRK)
+const FEE_TOKEN_DECIMALS: i64 = 18;
+
+/// Estimates what the relayer pays to deploy a vault
+///
+/// `deploy_vault` reverts unless the predicted vault already holds the deposit,
+/// so it cannot be simulated for a deposit that has not been funded. Instead,
+/// the estimate is the average actual fee of the most recent vault deployments
+/// recorded by the vault watcher.
+pub struct DeployFeeEstimator {
+    provider: JsonRpcClient<HttpTransport>,
+    orderbook: Arc<OrderbookProvider>,
+    cache: Cache<(), Option<DeployFee>>,
+}
+
+impl DeployFeeEstimator {
+    /// Creates a new DeployFeeEstimator instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - Starknet RPC provider used to fetch transaction receipts
+    /// * `orderbook` - Orderbook holding the deployment transaction hashes
+    pub fn new(provider: JsonRpcClient<HttpTransport>, orderbook: Arc<OrderbookProvider>) -> Self {
+        let cache = Cache::builder()
+            .time_to_live(Duration::from_secs(DEPLOY_FEE_CACHE_TTL_SECS))
+            .max_capacity(1)
+            .build();
+
+        Self {
+            provider,
+            orderbook,
+            cache,
+        }
+    }
+
+    /// Returns the estimated fee, or None if no deployment could be sampled
+    pub async fn estimate(&self) -> Option<DeployFee> {
+        self.cache
+            .try_get_with((), self.sample())
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to estimate the vault deployment fee: {}", e);
+                None
+            })
+    }
+
+    async fn sample(&self) -> Result<Option<DeployFee>> {
+        let tx_hashes = self
+            .orderbook
+            .get_recent_deploy_tx_hashes(DEPLOY_FEE_SAMPLE_SIZE)
+            .await?;
+
+        let fees = join_all(
+            tx_hashes
+                .iter()
+                .filter_map(|tx_hash| Felt::from_hex(tx_hash).ok())
+                .map(|tx_hash| self.actual_fee(tx_hash)),
+        )
+        .await;
+
+        Ok(average_fee(fees.into_iter().flatten().collect()))
+    }
+
+    /// Fetches the fee paid by a transaction, if its receipt is available
+    async fn actual_fee(&self, tx_hash: Felt) -> Option<(BigInt, PriceUnit)> {
+        let started = Instant::now();
+        let result = self
+            .provider
+            .get_transaction_receipt(tx_hash)
+            .instrument(upstream_span(
+                UPSTREAM_STARKNET_RPC,
+                "get_transaction_receipt",
+            ))
+            .await;
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        METRICS.observe_upstream(
+            UPSTREAM_STARKNET_RPC,
+            "get_transaction_receipt",
+            outcome,
+            started,
+        );
+
+        match result {
+            Ok(receipt) => match receipt.receipt {
+                TransactionReceipt::Invoke(receipt) => Some((
+                    BigInt::from_bytes_be(Sign::Plus, &receipt.actual_fee.amount.to_bytes_be()),
+                    receipt.actual_fee.unit,
+                )),
+                _ => None,
+            },
+            Err(e) => {
+                warn!(tx_hash = %format!("{:#x}", tx_hash), "Failed to fetch receipt: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// Averages the fees paid in the most common unit
+fn average_fee(fees: Vec<(BigInt, PriceUnit)>) -> Option<DeployFee> {
+    let fri = fees
+        .iter()
+        .filter(|(_, unit)| *unit == PriceUnit::Fri)
+        .count();
+    let unit = if fri * 2 >= fees.len() {
+        PriceUnit::Fri
+    } else {
+        PriceUnit::Wei
+    };
+
+    let amounts: Vec<&BigInt> = fees
+        .iter()
+        .filter(|(_, fee_unit)| *fee_unit == unit)
+        .map(|(amount, _)| amount)
+        .collect();
+    if amounts.is_empty() {
+        return None;
+    }
+
+    let total: BigInt = amounts.iter().copied().sum();
+    let average =
+        BigDecimal::new(total, FEE_TOKEN_DECIMALS) / BigDecimal::from(amounts.len() as u64);
+
+    Some(DeployFee {
+        amount: average.with_scale(FEE_TOKEN_DECIMALS).normalized(),
+        token: match unit {
+            PriceUnit::Fri => "STRK",
+            PriceUnit::Wei => "ETH",
+        }
+        .to_string(),
+        samples: amounts.len(),
+        usd_value: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_average_fee_uses_most_common_unit() {
+        let fee = average_fee(vec![
+            (BigInt::from(1_000_000_000_000_000_000u64), PriceUnit::Fri),
+            (BigInt::from(2_000_000_000_000_000_000u64), PriceUnit::Fri),
+            (BigInt::from(5u8), PriceUnit::Wei),
+        ])
+        .unwrap();
+        assert_eq!(fee.token, "STRK");
+        assert_eq!(fee.amount.to_string(), "1.5");
+        assert_eq!(fee.samples, 2);
+
+        assert!(average_fee(vec![]).is_none());
+    }
+}
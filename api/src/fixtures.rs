@@ -0,0 +1,60 @@
+//! Values shared by the unit tests
+
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+
+use crate::primitives::{Asset, DepositResponse, DepositStatus, VesuToken};
+
+/// Parses a decimal literal
+pub fn decimal(value: &str) -> BigDecimal {
+    BigDecimal::from_str(value).unwrap()
+}
+
+/// A supported asset without minimum amount
+pub fn asset(symbol: &str, coingecko_id: &str, address: &str, decimals: u8) -> Asset {
+    Asset {
+        name: symbol.to_string(),
+        symbol: symbol.to_string(),
+        decimals,
+        coingecko_id: coingecko_id.to_string(),
+        address: address.to_string(),
+        min_amount: None,
+        price: None,
+    }
+}
+
+/// Wrapped BTC at address `0x0a`, priced by Coingecko as `bitcoin`
+pub fn wbtc() -> Asset {
+    Asset {
+        name: "Wrapped BTC".to_string(),
+        ..asset("WBTC", "bitcoin", "0x0a", 8)
+    }
+}
+
+/// A token as described by Vesu
+pub fn vesu_token(address: &str, symbol: &str, decimals: u32) -> VesuToken {
+    VesuToken {
+        address: address.to_string(),
+        name: symbol.to_string(),
+        symbol: symbol.to_string(),
+        decimals,
+    }
+}
+
+/// A created deposit `d1` of 0.25 [`wbtc`] by user `0x123` into target `0xb`
+pub fn deposit() -> DepositResponse {
+    DepositResponse {
+        deposit_id: "d1".to_string(),
+        user_address: "0x123".to_string(),
+        action: 0,
+        amount: decimal("25000000"),
+        token: "0xa".to_string(),
+        target_address: "0xb".to_string(),
+        deposit_address: "0xd".to_string(),
+        status: DepositStatus::Created,
+        created_at: chrono::DateTime::from_timestamp(1_760_000_000, 0).unwrap(),
+        deposit_tx_hash: None,
+        atomiq_swap_id: None,
+    }
+}
@@ -1,22 +1,45 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
+use futures_util::future::join_all;
 use reqwest::Url;
 use starknet::{
-    core::types::Felt,
+    core::{types::Felt, utils::cairo_short_string_to_felt},
     providers::{JsonRpcClient, jsonrpc::HttpTransport},
+    signers::{LocalWallet, SigningKey},
 };
 
+use tokio::sync::watch;
+use tracing::{info, warn};
+
 use crate::{orderbook::OrderbookProvider, settings::Settings};
 
+mod chain_id;
 mod coingecko;
+mod erc20;
+mod erc4626;
+mod fees;
+#[cfg(test)]
+mod fixtures;
+mod metrics;
 mod orderbook;
 mod primitives;
+mod recovery;
 mod registry;
 mod server;
 mod settings;
+mod shutdown;
+mod stats;
+mod vesu;
+mod webhooks;
 
 const SETTINGS_FILE_NAME: &str = "settings.json";
 
+/// Default maximum age of a cached price before `/ready` reports it stale
+const DEFAULT_MAX_PRICE_AGE_SECS: u64 = 300;
+
+/// Default time allowed for draining requests and stopping background work
+const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt().init();
@@ -37,32 +60,189 @@ async fn main() {
         settings.coingecko.cache_ttl_secs,
     );
 
-    let coingecko = Arc::new(coingecko);
-    let coingecko_clone = Arc::clone(&coingecko);
+    let (shutdown_sender, shutdown) = watch::channel(false);
     tokio::spawn(async move {
-        coingecko_clone.start().await;
+        shutdown::signal().await;
+        let _ = shutdown_sender.send(true);
     });
 
+    // Background loops that must finish before exiting
+    let mut background_tasks = Vec::new();
+
+    let coingecko = Arc::new(coingecko);
+    let coingecko_clone = Arc::clone(&coingecko);
+    let coingecko_shutdown = shutdown.clone();
+    background_tasks.push(tokio::spawn(async move {
+        coingecko_clone.start(coingecko_shutdown).await;
+    }));
+
     let provider: JsonRpcClient<HttpTransport> = JsonRpcClient::new(HttpTransport::new(
         Url::parse(settings.rpc_url.as_str()).expect("Invalid RPC URL"),
     ));
 
+    let expected_chain_id = settings
+        .readiness
+        .expected_chain_id
+        .as_deref()
+        .map(|chain_id| {
+            if chain_id.starts_with("0x") {
+                Felt::from_hex(chain_id).expect("Invalid expected chain id")
+            } else {
+                cairo_short_string_to_felt(chain_id).expect("Invalid expected chain id")
+            }
+        });
+    let chain_id = Arc::new(chain_id::ChainId::new(provider.clone(), expected_chain_id));
+    // Read now unless configured, but an unreachable RPC does not stop the API:
+    // /ready reports it and the chain ID is read again on first use
+    if let Err(e) = chain_id.get().await {
+        warn!("Failed to get chain id, retrying on first use: {}", e);
+    }
+
+    let authenticator = server::auth::RequestAuthenticator::new(
+        settings.auth.enabled,
+        Arc::clone(&chain_id),
+        settings.auth.nonce_ttl_secs,
+        provider.clone(),
+    );
+
     let vault_registry = registry::VaultRegistry::new(
         Felt::from_hex_unchecked(settings.vault_registry_address.as_str()),
-        provider,
+        provider.clone(),
     );
 
     let orderbook = OrderbookProvider::from_db_url(&settings.db_url)
         .await
         .expect("Failed to create orderbook provider");
 
+    let deposit_changes = Arc::new(orderbook::changes::DepositChangeFeed::new());
+    let deposit_changes_clone = Arc::clone(&deposit_changes);
+    let pool = orderbook.pool.clone();
+    tokio::spawn(async move {
+        deposit_changes_clone.start(pool).await;
+    });
+
+    let orderbook = Arc::new(orderbook);
+
+    let partner_tokens = match &settings.webhooks {
+        Some(webhooks) => {
+            let dispatcher = webhooks::WebhookDispatcher::new(
+                Arc::clone(&orderbook),
+                webhooks.poll_interval_secs,
+                webhooks.max_attempts,
+            );
+            let dispatcher_shutdown = shutdown.clone();
+            background_tasks.push(tokio::spawn(async move {
+                dispatcher.start(dispatcher_shutdown).await;
+            }));
+
+            webhooks
+                .partners
+                .iter()
+                .map(|(partner, token)| (token.clone(), partner.clone()))
+                .collect()
+        }
+        None => HashMap::new(),
+    };
+
+    let (operator_tokens, recovery) = match &settings.admin {
+        Some(admin) => {
+            let recovery = match (&admin.account_address, &admin.private_key) {
+                (Some(account_address), Some(private_key)) => {
+                    let signer = LocalWallet::from(SigningKey::from_secret_scalar(
+                        Felt::from_hex(private_key).expect("Invalid admin private key"),
+                    ));
+                    Some(Arc::new(recovery::RecoveryAccount::new(
+                        provider.clone(),
+                        signer,
+                        Felt::from_hex(account_address).expect("Invalid admin account address"),
+                        Arc::clone(&chain_id),
+                    )))
+                }
+                _ => None,
+            };
+
+            let operator_tokens = admin
+                .operators
+                .iter()
+                .map(|(operator, token)| (token.clone(), operator.clone()))
+                .collect();
+            (operator_tokens, recovery)
+        }
+        None => (HashMap::new(), None),
+    };
+
+    let handler_state = server::handler::HandlerState {
+        coingecko,
+        deploy_fees: Arc::new(fees::DeployFeeEstimator::new(
+            provider.clone(),
+            Arc::clone(&orderbook),
+        )),
+        stats: Arc::new(stats::DepositStatsProvider::new(
+            Arc::clone(&orderbook),
+            settings.stats_cache_ttl_secs,
+        )),
+        supported_assets: settings.supported_assets,
+        vesu: Arc::new(vesu::VesuClient::new(
+            &settings.vesu_api_base_url,
+            settings.vesu.timeout_secs,
+            settings.vesu.max_retries,
+            settings.vesu.pools_fresh_secs,
+            settings.vesu.pools_stale_secs,
+        )),
+        vault_registry: Arc::new(vault_registry),
+        orderbook,
+        authenticator: Arc::new(authenticator),
+        deposit_changes,
+        partner_tokens,
+        operator_tokens,
+        recovery,
+        provider,
+        chain_id,
+        max_price_age_secs: settings
+            .readiness
+            .max_price_age_secs
+            .unwrap_or(DEFAULT_MAX_PRICE_AGE_SECS),
+        shutdown: shutdown.clone(),
+    };
+
+    let rate_limiter = settings
+        .rate_limit
+        .map(|rate_limit| Arc::new(server::rate_limit::RateLimiter::new(rate_limit)));
+
+    let legacy_sunset = settings
+        .legacy_routes_sunset
+        .unwrap_or_else(server::versioning::LegacyRoutes::default_sunset);
+    let docs_html = settings
+        .docs
+        .as_ref()
+        .map(|docs| server::openapi::docs_html(docs).expect("Invalid docs settings"));
     let server = server::Server::new(
         settings.port,
-        coingecko,
-        settings.supported_assets,
-        settings.vesu_api_base_url,
-        Arc::new(vault_registry),
-        Arc::new(orderbook),
+        handler_state,
+        rate_limiter,
+        legacy_sunset,
+        docs_html,
     );
-    server.run().await;
+
+    let shutdown_timeout = Duration::from_secs(
+        settings
+            .shutdown_timeout_secs
+            .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_SECS),
+    );
+    let mut deadline_shutdown = shutdown.clone();
+    let deadline = async move {
+        shutdown::requested(&mut deadline_shutdown).await;
+        tokio::time::sleep(shutdown_timeout).await;
+    };
+
+    tokio::select! {
+        _ = async {
+            server.run(shutdown).await;
+            join_all(background_tasks).await;
+        } => info!("Shutdown complete"),
+        _ = deadline => warn!(
+            timeout_secs = shutdown_timeout.as_secs(),
+            "Shutdown deadline elapsed, exiting with work in flight"
+        ),
+    }
 }
@@ -0,0 +1,235 @@
+use std::{
+    sync::{Arc, LazyLock},
+    time::Instant,
+};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response as AxumResponse},
+};
+use prometheus::{
+    Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+
+use crate::server::handler::HandlerState;
+
+/// Upstream label for the Vesu API
+pub const UPSTREAM_VESU: &str = "vesu";
+
+/// Upstream label for the Coingecko price API
+pub const UPSTREAM_COINGECKO: &str = "coingecko";
+
+/// Upstream label for the Starknet JSON-RPC endpoint
+pub const UPSTREAM_STARKNET_RPC: &str = "starknet_rpc";
+
+/// Span around a call to an upstream service, with the labels of its metrics
+pub fn upstream_span(upstream: &str, operation: &str) -> tracing::Span {
+    tracing::info_span!("upstream", upstream, operation)
+}
+
+/// Latency buckets in seconds shared by the request and upstream histograms
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Process-wide metrics registry
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Prometheus metrics exported on `GET /metrics`
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    upstream_requests: IntCounterVec,
+    upstream_request_duration: HistogramVec,
+    price_age: GaugeVec,
+    db_pool_connections: IntGaugeVec,
+}
+
+impl Metrics {
+    /// Creates and registers all metrics
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a metric is invalid or registered twice.
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("onesat_api".to_string()), None)
+            .expect("Invalid metrics prefix");
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by route and status"),
+            &["method", "route", "status"],
+        )
+        .expect("Invalid metric");
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "HTTP request latency by route",
+            )
+            .buckets(LATENCY_BUCKETS.to_vec()),
+            &["method", "route"],
+        )
+        .expect("Invalid metric");
+        let upstream_requests = IntCounterVec::new(
+            Opts::new(
+                "upstream_requests_total",
+                "Calls to upstream services by outcome",
+            ),
+            &["upstream", "operation", "outcome"],
+        )
+        .expect("Invalid metric");
+        let upstream_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "upstream_request_duration_seconds",
+                "Latency of calls to upstream services",
+            )
+            .buckets(LATENCY_BUCKETS.to_vec()),
+            &["upstream", "operation"],
+        )
+        .expect("Invalid metric");
+        let price_age = GaugeVec::new(
+            Opts::new(
+                "price_age_seconds",
+                "Seconds since the cached price of an asset was refreshed",
+            ),
+            &["coin_id"],
+        )
+        .expect("Invalid metric");
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database pool connections by state"),
+            &["state"],
+        )
+        .expect("Invalid metric");
+
+        for collector in [
+            Box::new(http_requests.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(http_request_duration.clone()),
+            Box::new(upstream_requests.clone()),
+            Box::new(upstream_request_duration.clone()),
+            Box::new(price_age.clone()),
+            Box::new(db_pool_connections.clone()),
+        ] {
+            registry.register(collector).expect("Duplicate metric");
+        }
+
+        Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            upstream_requests,
+            upstream_request_duration,
+            price_age,
+            db_pool_connections,
+        }
+    }
+
+    /// Records the outcome and latency of a call to an upstream service
+    ///
+    /// # Arguments
+    /// * `upstream` - One of the `UPSTREAM_*` labels
+    /// * `operation` - The call made, e.g. `predict_address`
+    /// * `outcome` - `ok`, or a short error class such as `http_error`
+    /// * `started` - When the call started
+    pub fn observe_upstream(
+        &self,
+        upstream: &str,
+        operation: &str,
+        outcome: &str,
+        started: Instant,
+    ) {
+        self.upstream_requests
+            .with_label_values(&[upstream, operation, outcome])
+            .inc();
+        self.upstream_request_duration
+            .with_label_values(&[upstream, operation])
+            .observe(started.elapsed().as_secs_f64());
+    }
+
+    /// Encodes all metrics in the Prometheus text format
+    fn encode(&self) -> String {
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+/// Middleware recording the count and latency of every request per route
+///
+/// Requests that do not match a route share the `unmatched` label so that
+/// arbitrary paths cannot blow up the label cardinality.
+pub async fn track_requests(request: Request, next: Next) -> AxumResponse {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let response = next.run(request).await;
+
+    METRICS
+        .http_requests
+        .with_label_values(&[&method, &route, response.status().as_str()])
+        .inc();
+    METRICS
+        .http_request_duration
+        .with_label_values(&[&method, &route])
+        .observe(started.elapsed().as_secs_f64());
+
+    response
+}
+
+/// Serves all metrics in the Prometheus text format
+///
+/// Gauges derived from application state (cached price age and database pool
+/// usage) are refreshed on every scrape.
+pub async fn metrics(State(state): State<Arc<HandlerState>>) -> AxumResponse {
+    let now = chrono::Utc::now();
+    for (coin_id, updated_at) in state.coingecko.price_updated_at() {
+        let age = (now - updated_at).num_milliseconds() as f64 / 1000.0;
+        METRICS.price_age.with_label_values(&[&coin_id]).set(age);
+    }
+
+    let pool = &state.orderbook.pool;
+    let idle = pool.num_idle() as i64;
+    let size = pool.size() as i64;
+    METRICS
+        .db_pool_connections
+        .with_label_values(&["idle"])
+        .set(idle);
+    METRICS
+        .db_pool_connections
+        .with_label_values(&["in_use"])
+        .set(size - idle);
+    METRICS
+        .db_pool_connections
+        .with_label_values(&["max"])
+        .set(pool.options().get_max_connections() as i64);
+
+    (
+        [(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+        METRICS.encode(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upstream_metrics_are_exported() {
+        METRICS.observe_upstream(UPSTREAM_VESU, "pools", "ok", Instant::now());
+        let text = METRICS.encode();
+        assert!(text.contains(
+            r#"onesat_api_upstream_requests_total{operation="pools",outcome="ok",upstream="vesu"}"#
+        ));
+        assert!(text.contains("onesat_api_upstream_request_duration_seconds_bucket"));
+    }
+}
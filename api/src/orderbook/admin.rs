@@ -0,0 +1,209 @@
+use eyre::Result;
+
+use crate::{
+    orderbook::OrderbookProvider,
+    primitives::{AuditAction, DepositAuditEntry, DepositResponse, DepositStatus},
+};
+
+impl OrderbookProvider {
+    /// Overwrites the status of a deposit and records the change in the audit trail
+    ///
+    /// # Arguments
+    /// * `deposit_id` - The deposit ID
+    /// * `status` - Status to move the deposit to
+    /// * `operator` - Operator taking the action
+    /// * `reason` - Reason given by the operator
+    ///
+    /// # Returns
+    /// The updated deposit, or None if it does not exist
+    pub async fn force_deposit_status(
+        &self,
+        deposit_id: &str,
+        status: &DepositStatus,
+        operator: &str,
+        reason: &str,
+    ) -> Result<Option<DepositResponse>> {
+        self.transition_deposit(
+            deposit_id,
+            status,
+            false,
+            AuditAction::ForceStatus,
+            operator,
+            reason,
+        )
+        .await
+    }
+
+    /// Moves a deposit back to `created` so that the vault watcher deploys it again
+    ///
+    /// The recorded deployment transaction hash is cleared, as it belongs to the
+    /// previous attempt.
+    ///
+    /// # Arguments
+    /// * `deposit_id` - The deposit ID
+    /// * `operator` - Operator taking the action
+    /// * `reason` - Reason given by the operator
+    ///
+    /// # Returns
+    /// The updated deposit, or None if it does not exist
+    pub async fn requeue_deposit(
+        &self,
+        deposit_id: &str,
+        operator: &str,
+        reason: &str,
+    ) -> Result<Option<DepositResponse>> {
+        self.transition_deposit(
+            deposit_id,
+            &DepositStatus::Created,
+            true,
+            AuditAction::Requeue,
+            operator,
+            reason,
+        )
+        .await
+    }
+
+    /// Updates the status and writes the audit entry in a single transaction
+    async fn transition_deposit(
+        &self,
+        deposit_id: &str,
+        status: &DepositStatus,
+        clear_deposit_tx_hash: bool,
+        action: AuditAction,
+        operator: &str,
+        reason: &str,
+    ) -> Result<Option<DepositResponse>> {
+        let mut tx = self.pool.begin().await?;
+
+        let from_status = sqlx::query_scalar::<_, DepositStatus>(
+            r#"
+            SELECT status
+            FROM deposits
+            WHERE deposit_id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(deposit_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(from_status) = from_status else {
+            tx.rollback().await?;
+            return Ok(None);
+        };
+
+        let deposit = sqlx::query_as::<_, DepositResponse>(
+            r#"
+            UPDATE deposits
+            SET
+                status = $2,
+                deposit_tx_hash = CASE WHEN $3 THEN NULL ELSE deposit_tx_hash END,
+                cancelled_at = CASE
+                    WHEN $2 = 'cancelled' AND status <> 'cancelled' THEN NOW()
+                    ELSE cancelled_at
+                END
+            WHERE deposit_id = $1
+            RETURNING
+                deposit_id,
+                user_address,
+                action,
+                amount,
+                token,
+                target_address,
+                deposit_address,
+                status,
+                created_at,
+                deposit_tx_hash,
+                atomiq_swap_id
+            "#,
+        )
+        .bind(deposit_id)
+        .bind(status.to_string())
+        .bind(clear_deposit_tx_hash)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO deposit_audit_log (
+                deposit_id, operator, action, from_status, to_status, reason
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(deposit_id)
+        .bind(operator)
+        .bind(action.to_string())
+        .bind(from_status.to_string())
+        .bind(status.to_string())
+        .bind(reason)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(deposit))
+    }
+
+    /// Records an action that does not change the deposit status
+    ///
+    /// # Arguments
+    /// * `deposit_id` - The deposit ID
+    /// * `operator` - Operator taking the action
+    /// * `action` - The action taken
+    /// * `reason` - Reason given by the operator
+    /// * `tx_hash` - Transaction sent by the action, if any
+    ///
+    /// # Returns
+    /// The recorded audit entry
+    pub async fn record_deposit_audit(
+        &self,
+        deposit_id: &str,
+        operator: &str,
+        action: AuditAction,
+        reason: &str,
+        tx_hash: Option<&str>,
+    ) -> Result<DepositAuditEntry> {
+        let entry = sqlx::query_as::<_, DepositAuditEntry>(
+            r#"
+            INSERT INTO deposit_audit_log (deposit_id, operator, action, reason, tx_hash)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING
+                id, deposit_id, operator, action, from_status, to_status,
+                reason, tx_hash, created_at
+            "#,
+        )
+        .bind(deposit_id)
+        .bind(operator)
+        .bind(action.to_string())
+        .bind(reason)
+        .bind(tx_hash)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Retrieves the audit trail of a deposit
+    ///
+    /// # Arguments
+    /// * `deposit_id` - The deposit ID
+    ///
+    /// # Returns
+    /// Every administrative action taken on the deposit, newest first
+    pub async fn get_deposit_audit_log(&self, deposit_id: &str) -> Result<Vec<DepositAuditEntry>> {
+        let entries = sqlx::query_as::<_, DepositAuditEntry>(
+            r#"
+            SELECT
+                id, deposit_id, operator, action, from_status, to_status,
+                reason, tx_hash, created_at
+            FROM deposit_audit_log
+            WHERE deposit_id = $1
+            ORDER BY id DESC
+            "#,
+        )
+        .bind(deposit_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use bigdecimal::BigDecimal;
+use eyre::{Result, eyre};
+use sqlx::{Postgres, QueryBuilder, Transaction};
+
+use crate::{
+    orderbook::{
+        OrderbookProvider,
+        idempotency::{BatchOutcome, IdempotencyKey, IdempotencyKeyTaken},
+    },
+    primitives::DepositResponse,
+};
+
+/// A deposit to insert as part of a batch
+pub struct NewDeposit<'a> {
+    pub deposit_id: String,
+    pub user_address: &'a str,
+    pub action: u128,
+    pub amount: &'a BigDecimal,
+    pub token: &'a str,
+    pub target_address: &'a str,
+    pub deposit_address: String,
+    /// USD price of one token unit at creation time, if known
+    pub usd_price: Option<BigDecimal>,
+    /// ID of the API request creating the deposit
+    pub request_id: &'a str,
+}
+
+impl OrderbookProvider {
+    /// Creates several deposits in a single statement, and so in one transaction
+    ///
+    /// # Arguments
+    /// * `deposits` - The deposits to insert, all with status "created"
+    /// * `idempotency` - Optional idempotency key of the batch, stored in the
+    ///   same transaction with the outcome of every requested deposit
+    ///
+    /// # Returns
+    /// The created deposit records, in the order of `deposits`. Either all
+    /// deposits are created or none is. Fails with [`IdempotencyKeyTaken`] if
+    /// the idempotency key was stored by another request in the meantime.
+    pub async fn create_deposits(
+        &self,
+        deposits: &[NewDeposit<'_>],
+        idempotency: Option<(&IdempotencyKey, &[BatchOutcome])>,
+    ) -> Result<Vec<DepositResponse>> {
+        let mut tx = self.pool.begin().await?;
+        let created = if deposits.is_empty() {
+            Vec::new()
+        } else {
+            insert_deposits(&mut tx, deposits).await?
+        };
+
+        if let Some((key, outcomes)) = idempotency {
+            let stored = sqlx::query(
+                r#"
+                INSERT INTO deposit_batch_idempotency_keys (
+                    scope, idempotency_key, request_hash, outcomes
+                )
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(&key.scope)
+            .bind(&key.key)
+            .bind(&key.request_hash)
+            .bind(sqlx::types::Json(outcomes))
+            .execute(&mut *tx)
+            .await?;
+
+            if stored.rows_affected() == 0 {
+                tx.rollback().await?;
+                return Err(IdempotencyKeyTaken.into());
+            }
+        }
+
+        tx.commit().await?;
+
+        // RETURNING does not guarantee the order of the inserted rows
+        let mut created: HashMap<String, DepositResponse> = created
+            .into_iter()
+            .map(|deposit| (deposit.deposit_id.clone(), deposit))
+            .collect();
+        deposits
+            .iter()
+            .map(|deposit| {
+                created
+                    .remove(&deposit.deposit_id)
+                    .ok_or_else(|| eyre!("Deposit {} was not returned", deposit.deposit_id))
+            })
+            .collect()
+    }
+
+    /// Retrieves deposits by their IDs
+    ///
+    /// # Arguments
+    /// * `deposit_ids` - The deposit IDs
+    ///
+    /// # Returns
+    /// The deposits found, in no particular order
+    pub async fn get_deposits_by_ids(
+        &self,
+        deposit_ids: &[String],
+    ) -> Result<Vec<DepositResponse>> {
+        let deposits = sqlx::query_as::<_, DepositResponse>(
+            r#"
+            SELECT
+                deposit_id,
+                user_address,
+                action,
+                amount,
+                token,
+                target_address,
+                deposit_address,
+                status,
+                created_at,
+                deposit_tx_hash,
+                atomiq_swap_id
+            FROM deposits
+            WHERE deposit_id = ANY($1)
+            "#,
+        )
+        .bind(deposit_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deposits)
+    }
+}
+
+/// Inserts deposits with one multi-row statement
+async fn insert_deposits(
+    tx: &mut Transaction<'_, Postgres>,
+    deposits: &[NewDeposit<'_>],
+) -> Result<Vec<DepositResponse>> {
+    let created_at = chrono::Utc::now();
+    let mut query = QueryBuilder::<Postgres>::new(
+        r#"
+        INSERT INTO deposits (
+            deposit_id, user_address, action, amount,
+            token, target_address, deposit_address, status,
+            created_at, usd_price, request_id
+        )
+        "#,
+    );
+    query.push_values(deposits, |mut row, deposit| {
+        row.push_bind(&deposit.deposit_id)
+            .push_bind(deposit.user_address)
+            .push_bind(deposit.action as i64)
+            .push_bind(deposit.amount)
+            .push_bind(deposit.token)
+            .push_bind(deposit.target_address)
+            .push_bind(&deposit.deposit_address)
+            .push_bind("created")
+            .push_bind(created_at)
+            .push_bind(&deposit.usd_price)
+            .push_bind(deposit.request_id);
+    });
+    query.push(
+        r#"
+        RETURNING
+            deposit_id,
+            user_address,
+            action,
+            amount,
+            token,
+            target_address,
+            deposit_address,
+            status,
+            created_at,
+            deposit_tx_hash,
+            atomiq_swap_id
+        "#,
+    );
+
+    let created = query
+        .build_query_as::<DepositResponse>()
+        .fetch_all(&mut **tx)
+        .await?;
+
+    Ok(created)
+}
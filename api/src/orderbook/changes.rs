@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres, postgres::PgListener};
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use crate::primitives::{DepositResponse, DepositStatus};
+
+/// Postgres channel the `deposits_notify_change` trigger publishes on
+const DEPOSIT_CHANGES_CHANNEL: &str = "deposit_changes";
+
+/// Number of changes buffered per subscriber before it starts lagging
+const DEPOSIT_CHANGES_BUFFER: usize = 1024;
+
+/// Delay before reconnecting the listener after a failure
+const RECONNECT_DELAY_SECS: u64 = 5;
+
+/// The tracked fields of a deposit after a change
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct DepositChange {
+    /// Unique deposit identifier
+    pub deposit_id: String,
+    /// Current status of the deposit
+    pub status: DepositStatus,
+    /// Transaction hash of the deposit
+    pub deposit_tx_hash: Option<String>,
+    /// Atomiq swap id
+    pub atomiq_swap_id: Option<String>,
+}
+
+impl From<&DepositResponse> for DepositChange {
+    fn from(deposit: &DepositResponse) -> Self {
+        Self {
+            deposit_id: deposit.deposit_id.clone(),
+            status: deposit.status.clone(),
+            deposit_tx_hash: deposit.deposit_tx_hash.clone(),
+            atomiq_swap_id: deposit.atomiq_swap_id.clone(),
+        }
+    }
+}
+
+/// Change feed of the `deposits` table
+///
+/// A single Postgres `LISTEN` connection receives the notifications published by
+/// the `deposits_notify_change` trigger and fans them out to all subscribers, so
+/// the number of open streams does not affect database load.
+pub struct DepositChangeFeed {
+    sender: broadcast::Sender<DepositChange>,
+}
+
+impl Default for DepositChangeFeed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DepositChangeFeed {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(DEPOSIT_CHANGES_BUFFER);
+        Self { sender }
+    }
+
+    /// Subscribes to all deposit changes published after this call
+    pub fn subscribe(&self) -> broadcast::Receiver<DepositChange> {
+        self.sender.subscribe()
+    }
+
+    /// Starts listening for deposit changes.
+    ///
+    /// This method runs indefinitely, reconnecting the listener whenever it
+    /// fails, and should be spawned in a separate task.
+    pub async fn start(&self, pool: Pool<Postgres>) {
+        info!("Starting deposit change feed");
+        loop {
+            if let Err(e) = self.listen(&pool).await {
+                error!("Deposit change feed failed: {}", e);
+            }
+            tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+        }
+    }
+
+    async fn listen(&self, pool: &Pool<Postgres>) -> eyre::Result<()> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(DEPOSIT_CHANGES_CHANNEL).await?;
+
+        loop {
+            let notification = listener.recv().await?;
+            match serde_json::from_str::<DepositChange>(notification.payload()) {
+                // Sending only fails when nobody is subscribed
+                Ok(change) => {
+                    let _ = self.sender.send(change);
+                }
+                Err(e) => {
+                    warn!(
+                        payload = notification.payload(),
+                        "Invalid deposit change: {}", e
+                    )
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use bigdecimal::BigDecimal;
+use eyre::Result;
+use sqlx::{Postgres, QueryBuilder};
+
+use crate::{
+    orderbook::OrderbookProvider,
+    primitives::{DepositCursor, DepositResponse},
+};
+
+/// A deposit together with the token price recorded when it was created
+#[derive(Debug, sqlx::FromRow)]
+pub struct ExportedDeposit {
+    #[sqlx(flatten)]
+    pub deposit: DepositResponse,
+    pub usd_price: Option<BigDecimal>,
+}
+
+impl OrderbookProvider {
+    /// Retrieves a batch of a user's deposits for export, oldest first
+    ///
+    /// # Arguments
+    /// * `user_address` - The user's wallet address
+    /// * `created_after` - Only include deposits created at or after this time
+    /// * `created_before` - Only include deposits created strictly before this time
+    /// * `after` - Last deposit of the previous batch, if any
+    /// * `limit` - Maximum number of deposits to return
+    ///
+    /// # Returns
+    /// Up to `limit` deposits following `after` in `(created_at, deposit_id)` order
+    pub async fn get_deposits_for_export(
+        &self,
+        user_address: &str,
+        created_after: Option<chrono::DateTime<chrono::Utc>>,
+        created_before: Option<chrono::DateTime<chrono::Utc>>,
+        after: Option<&DepositCursor>,
+        limit: i64,
+    ) -> Result<Vec<ExportedDeposit>> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT
+                deposit_id,
+                user_address,
+                action,
+                amount,
+                token,
+                target_address,
+                deposit_address,
+                status,
+                created_at,
+                deposit_tx_hash,
+                atomiq_swap_id,
+                usd_price
+            FROM deposits
+            WHERE user_address = "#,
+        );
+        query.push_bind(user_address);
+
+        if let Some(created_after) = created_after {
+            query.push(" AND created_at >= ").push_bind(created_after);
+        }
+        if let Some(created_before) = created_before {
+            query.push(" AND created_at < ").push_bind(created_before);
+        }
+        if let Some(after) = after {
+            query
+                .push(" AND (created_at, deposit_id) > (")
+                .push_bind(after.created_at)
+                .push(", ")
+                .push_bind(&after.deposit_id)
+                .push(")");
+        }
+
+        query
+            .push(" ORDER BY created_at ASC, deposit_id ASC LIMIT ")
+            .push_bind(limit);
+
+        let deposits = query
+            .build_query_as::<ExportedDeposit>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(deposits)
+    }
+}
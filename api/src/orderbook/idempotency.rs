@@ -0,0 +1,93 @@
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{orderbook::OrderbookProvider, primitives::ApiErrorCode};
+
+/// An `Idempotency-Key` presented with a deposit creation request
+#[derive(Debug, Clone)]
+pub struct IdempotencyKey {
+    /// What the key is scoped to: the normalised user address for a deposit,
+    /// or a hash of the users of a batch
+    pub scope: String,
+    /// The key as sent by the client
+    pub key: String,
+    /// SHA-256 of the normalised request
+    pub request_hash: String,
+}
+
+/// A stored idempotency key and the deposit it produced
+#[derive(Debug, sqlx::FromRow)]
+pub struct IdempotencyRecord {
+    pub request_hash: String,
+    pub deposit_id: String,
+}
+
+/// What became of one deposit of a batch, stored with the batch's idempotency key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "lowercase")]
+pub enum BatchOutcome {
+    Created { deposit_id: String },
+    Rejected { code: ApiErrorCode, error: String },
+}
+
+/// A stored batch idempotency key and the outcomes of the batch
+#[derive(Debug, sqlx::FromRow)]
+pub struct BatchIdempotencyRecord {
+    pub request_hash: String,
+    /// One outcome per requested deposit, in request order
+    #[sqlx(json)]
+    pub outcomes: Vec<BatchOutcome>,
+}
+
+/// Returned by `create_deposit` when another request stored the same key first
+#[derive(Debug, thiserror::Error)]
+#[error("Idempotency key already used")]
+pub struct IdempotencyKeyTaken;
+
+impl OrderbookProvider {
+    /// Retrieves the record stored for an idempotency key
+    ///
+    /// # Returns
+    /// The record if the key was used before, None otherwise
+    pub async fn get_idempotency_record(
+        &self,
+        key: &IdempotencyKey,
+    ) -> Result<Option<IdempotencyRecord>> {
+        let record = sqlx::query_as::<_, IdempotencyRecord>(
+            r#"
+            SELECT request_hash, deposit_id
+            FROM deposit_idempotency_keys
+            WHERE user_address = $1 AND idempotency_key = $2
+            "#,
+        )
+        .bind(&key.scope)
+        .bind(&key.key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// Retrieves the record stored for the idempotency key of a batch
+    ///
+    /// # Returns
+    /// The record if the key was used before, None otherwise
+    pub async fn get_batch_idempotency_record(
+        &self,
+        key: &IdempotencyKey,
+    ) -> Result<Option<BatchIdempotencyRecord>> {
+        let record = sqlx::query_as::<_, BatchIdempotencyRecord>(
+            r#"
+            SELECT request_hash, outcomes
+            FROM deposit_batch_idempotency_keys
+            WHERE scope = $1 AND idempotency_key = $2
+            "#,
+        )
+        .bind(&key.scope)
+        .bind(&key.key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+}
@@ -1,8 +1,19 @@
 use bigdecimal::BigDecimal;
 use eyre::Result;
-use sqlx::{Pool, Postgres};
+use sqlx::{Pool, Postgres, QueryBuilder};
 
-use crate::primitives::DepositResponse;
+pub mod admin;
+pub mod batch;
+pub mod changes;
+pub mod export;
+pub mod idempotency;
+pub mod stats;
+pub mod webhooks;
+
+use crate::{
+    orderbook::idempotency::{IdempotencyKey, IdempotencyKeyTaken},
+    primitives::{DepositCursor, DepositFilter, DepositPage, DepositResponse},
+};
 
 pub struct OrderbookProvider {
     pub pool: Pool<Postgres>,
@@ -25,6 +36,12 @@ impl OrderbookProvider {
         Ok(Self::new(pool))
     }
 
+    /// Checks that a connection can be acquired and used
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
     /// Creates a new deposit record in the database
     ///
     /// # Arguments
@@ -35,9 +52,13 @@ impl OrderbookProvider {
     /// * `token` - Token contract address
     /// * `target_address` - Target address for the deposit
     /// * `deposit_address` - Generated deposit address from registry contract
+    /// * `usd_price` - USD price of one token unit at creation time, if known
+    /// * `request_id` - ID of the API request creating the deposit
+    /// * `idempotency_key` - Optional idempotency key stored in the same transaction
     ///
     /// # Returns
-    /// The created deposit record
+    /// The created deposit record, or an [`IdempotencyKeyTaken`] error if the
+    /// idempotency key was stored by another request in the meantime
     pub async fn create_deposit(
         &self,
         deposit_id: &str,
@@ -49,16 +70,21 @@ impl OrderbookProvider {
         deposit_address: &str,
         deposit_tx_hash: Option<String>,
         atomiq_swap_id: Option<String>,
+        usd_price: Option<&BigDecimal>,
+        request_id: &str,
+        idempotency_key: Option<&IdempotencyKey>,
     ) -> Result<DepositResponse> {
         let created_at = chrono::Utc::now();
+        let mut tx = self.pool.begin().await?;
         let deposit = sqlx::query_as::<_, DepositResponse>(
             r#"
             INSERT INTO deposits (
                 deposit_id, user_address, action, amount, 
                 token, target_address, deposit_address, status,
-                created_at, deposit_tx_hash, atomiq_swap_id
+                created_at, deposit_tx_hash, atomiq_swap_id, usd_price,
+                request_id
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
             RETURNING 
                 deposit_id,
                 user_address,
@@ -84,9 +110,35 @@ impl OrderbookProvider {
         .bind(created_at)
         .bind(deposit_tx_hash)
         .bind(atomiq_swap_id)
-        .fetch_one(&self.pool)
+        .bind(usd_price)
+        .bind(request_id)
+        .fetch_one(&mut *tx)
         .await?;
 
+        if let Some(key) = idempotency_key {
+            let stored = sqlx::query(
+                r#"
+                INSERT INTO deposit_idempotency_keys (
+                    user_address, idempotency_key, request_hash, deposit_id
+                )
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(&key.scope)
+            .bind(&key.key)
+            .bind(&key.request_hash)
+            .bind(deposit_id)
+            .execute(&mut *tx)
+            .await?;
+
+            if stored.rows_affected() == 0 {
+                tx.rollback().await?;
+                return Err(IdempotencyKeyTaken.into());
+            }
+        }
+
+        tx.commit().await?;
         Ok(deposit)
     }
 
@@ -123,17 +175,20 @@ impl OrderbookProvider {
         Ok(deposit)
     }
 
-    /// Retrieves all deposits with a specific status
+    /// Retrieves a deposit by its deposit address
     ///
     /// # Arguments
-    /// * `status` - The status to filter by ("created", "initiated", or "deposited")
+    /// * `deposit_address` - The deposit address, in canonical form (`{:#x}`)
     ///
     /// # Returns
-    /// A list of deposits matching the status
-    pub async fn get_deposits_by_status(&self, status: &str) -> Result<Vec<DepositResponse>> {
-        let deposits = sqlx::query_as::<_, DepositResponse>(
+    /// The deposit if found, None otherwise
+    pub async fn get_deposit_by_address(
+        &self,
+        deposit_address: &str,
+    ) -> Result<Option<DepositResponse>> {
+        let deposit = sqlx::query_as::<_, DepositResponse>(
             r#"
-            SELECT 
+            SELECT
                 deposit_id,
                 user_address,
                 action,
@@ -146,22 +201,35 @@ impl OrderbookProvider {
                 deposit_tx_hash,
                 atomiq_swap_id
             FROM deposits
-            WHERE status = $1
-            ORDER BY created_at DESC
+            WHERE deposit_address = $1
             "#,
         )
-        .bind(status)
-        .fetch_all(&self.pool)
+        .bind(deposit_address)
+        .fetch_optional(&self.pool)
         .await?;
 
-        Ok(deposits)
+        Ok(deposit)
     }
 
-    pub async fn get_deposits_by_user_address(
+    /// Retrieves a page of deposits matching the given filter
+    ///
+    /// Deposits are ordered by `(created_at, deposit_id)` descending so that
+    /// keyset pagination stays stable while new deposits are being inserted.
+    ///
+    /// # Arguments
+    /// * `filter` - Filters to apply to the listing
+    /// * `cursor` - Position after which to start the page, if any
+    /// * `limit` - Maximum number of deposits to return
+    ///
+    /// # Returns
+    /// The deposits in the page and the cursor for the next page, if any
+    pub async fn get_deposits(
         &self,
-        user_address: &str,
-    ) -> Result<Vec<DepositResponse>> {
-        let deposits = sqlx::query_as::<_, DepositResponse>(
+        filter: &DepositFilter,
+        cursor: Option<&DepositCursor>,
+        limit: i64,
+    ) -> Result<DepositPage> {
+        let mut query = QueryBuilder::<Postgres>::new(
             r#"
             SELECT 
                 deposit_id,
@@ -176,8 +244,96 @@ impl OrderbookProvider {
                 deposit_tx_hash,
                 atomiq_swap_id
             FROM deposits
-            WHERE user_address = $1
-            ORDER BY created_at DESC
+            WHERE TRUE
+            "#,
+        );
+
+        if let Some(user_address) = &filter.user_address {
+            query.push(" AND user_address = ").push_bind(user_address);
+        }
+        if let Some(status) = &filter.status {
+            query.push(" AND status = ").push_bind(status.to_string());
+        }
+        if let Some(token) = &filter.token {
+            query.push(" AND token = ").push_bind(token);
+        }
+        if let Some(action) = filter.action {
+            query.push(" AND action = ").push_bind(action as i64);
+        }
+        if let Some(target_address) = &filter.target_address {
+            query
+                .push(" AND target_address = ")
+                .push_bind(target_address);
+        }
+        if let Some(created_after) = filter.created_after {
+            query.push(" AND created_at >= ").push_bind(created_after);
+        }
+        if let Some(created_before) = filter.created_before {
+            query.push(" AND created_at < ").push_bind(created_before);
+        }
+        if let Some(cursor) = cursor {
+            query
+                .push(" AND (created_at, deposit_id) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(&cursor.deposit_id)
+                .push(")");
+        }
+
+        // Fetch one extra row to know whether another page follows
+        query
+            .push(" ORDER BY created_at DESC, deposit_id DESC LIMIT ")
+            .push_bind(limit + 1);
+
+        let mut deposits = query
+            .build_query_as::<DepositResponse>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        let next_cursor = if deposits.len() as i64 > limit {
+            deposits.truncate(limit as usize);
+            deposits.last().map(|deposit| {
+                DepositCursor {
+                    created_at: deposit.created_at,
+                    deposit_id: deposit.deposit_id.clone(),
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
+
+        Ok(DepositPage {
+            deposits,
+            next_cursor,
+        })
+    }
+
+    /// Retrieves all deposits of a user that have been neither deposited nor cancelled
+    ///
+    /// # Arguments
+    /// * `user_address` - The user's wallet address
+    ///
+    /// # Returns
+    /// The user's pending deposits, newest first
+    pub async fn get_pending_deposits(&self, user_address: &str) -> Result<Vec<DepositResponse>> {
+        let deposits = sqlx::query_as::<_, DepositResponse>(
+            r#"
+            SELECT
+                deposit_id,
+                user_address,
+                action,
+                amount,
+                token,
+                target_address,
+                deposit_address,
+                status,
+                created_at,
+                deposit_tx_hash,
+                atomiq_swap_id
+            FROM deposits
+            WHERE user_address = $1 AND status NOT IN ('deposited', 'cancelled')
+            ORDER BY created_at DESC, deposit_id DESC
             "#,
         )
         .bind(user_address)
@@ -187,6 +343,64 @@ impl OrderbookProvider {
         Ok(deposits)
     }
 
+    /// Retrieves the hashes of the most recent vault deployment transactions
+    ///
+    /// # Arguments
+    /// * `limit` - Maximum number of hashes to return
+    ///
+    /// # Returns
+    /// Transaction hashes of the latest `deposited` deposits, newest first
+    pub async fn get_recent_deploy_tx_hashes(&self, limit: i64) -> Result<Vec<String>> {
+        let hashes = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT deposit_tx_hash
+            FROM deposits
+            WHERE status = 'deposited' AND deposit_tx_hash IS NOT NULL
+            ORDER BY created_at DESC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(hashes)
+    }
+
+    /// Cancels a deposit that is still waiting for funds
+    ///
+    /// # Arguments
+    /// * `deposit_id` - The deposit ID to cancel
+    ///
+    /// # Returns
+    /// The cancelled deposit, or None if it does not exist or is no longer `created`
+    pub async fn cancel_deposit(&self, deposit_id: &str) -> Result<Option<DepositResponse>> {
+        let deposit = sqlx::query_as::<_, DepositResponse>(
+            r#"
+            UPDATE deposits
+            SET status = 'cancelled', cancelled_at = NOW()
+            WHERE deposit_id = $1 AND status = 'created'
+            RETURNING
+                deposit_id,
+                user_address,
+                action,
+                amount,
+                token,
+                target_address,
+                deposit_address,
+                status,
+                created_at,
+                deposit_tx_hash,
+                atomiq_swap_id
+            "#,
+        )
+        .bind(deposit_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(deposit)
+    }
+
     /// Updates the atomiq swap id for a deposit
     ///
     /// # Arguments
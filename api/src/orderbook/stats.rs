@@ -0,0 +1,128 @@
+use bigdecimal::BigDecimal;
+use eyre::Result;
+
+use crate::{
+    orderbook::OrderbookProvider,
+    primitives::{DepositStatus, StatsInterval},
+};
+
+/// Number and total amount of the deposits of one token in one status
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TokenStatusTotals {
+    pub token: String,
+    pub status: DepositStatus,
+    pub deposits: i64,
+    pub amount: BigDecimal,
+}
+
+/// Aggregates over the whole deposits table
+#[derive(Debug, Clone)]
+pub struct DepositTotals {
+    pub by_token_status: Vec<TokenStatusTotals>,
+    pub unique_users: i64,
+}
+
+/// Deposits of one token created during one time bucket
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct BucketTotals {
+    /// Start of the bucket
+    pub bucket: chrono::DateTime<chrono::Utc>,
+    /// Token of the deposits, None for a bucket without deposits
+    pub token: Option<String>,
+    /// Number of deposits created
+    pub deposits: i64,
+    /// Number of those deposits that are deposited
+    pub deposited: i64,
+    /// Total amount of the deposited ones, in base units
+    pub deposited_amount: BigDecimal,
+}
+
+impl OrderbookProvider {
+    /// Aggregates all deposits by token and status
+    ///
+    /// # Returns
+    /// The number and total amount of deposits per token and status, and the
+    /// number of distinct users. Users are counted by address ignoring case and
+    /// leading zeros, like tokens are merged by the stats handler.
+    pub async fn get_deposit_totals(&self) -> Result<DepositTotals> {
+        let by_token_status = sqlx::query_as::<_, TokenStatusTotals>(
+            r#"
+            SELECT
+                token,
+                status,
+                COUNT(*) AS deposits,
+                SUM(amount) AS amount
+            FROM deposits
+            GROUP BY token, status
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let unique_users = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(DISTINCT '0x' || COALESCE(
+                NULLIF(ltrim(lower(regexp_replace(user_address, '^0[xX]', '')), '0'), ''),
+                '0'
+            ))
+            FROM deposits
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(DepositTotals {
+            by_token_status,
+            unique_users,
+        })
+    }
+
+    /// Aggregates the deposits created in the most recent time buckets
+    ///
+    /// # Arguments
+    /// * `interval` - The length of a bucket; buckets start at UTC boundaries
+    /// * `buckets` - The number of buckets, including the current one
+    ///
+    /// # Returns
+    /// Totals per bucket and token, oldest bucket first. Buckets without
+    /// deposits are returned as a single row without token.
+    pub async fn get_deposit_timeseries(
+        &self,
+        interval: StatsInterval,
+        buckets: i64,
+    ) -> Result<Vec<BucketTotals>> {
+        let totals = sqlx::query_as::<_, BucketTotals>(
+            r#"
+            WITH buckets AS (
+                SELECT
+                    bucket AT TIME ZONE 'UTC' AS bucket_start,
+                    (bucket + step) AT TIME ZONE 'UTC' AS bucket_end
+                FROM
+                    (
+                        SELECT
+                            ('1 ' || $1)::interval AS step,
+                            date_trunc($1, now() AT TIME ZONE 'UTC') AS latest
+                    ) params,
+                    generate_series(latest - ($2::bigint - 1) * step, latest, step) AS bucket
+            )
+            SELECT
+                b.bucket_start AS bucket,
+                d.token,
+                COUNT(d.deposit_id) AS deposits,
+                COUNT(d.deposit_id) FILTER (WHERE d.status = 'deposited') AS deposited,
+                COALESCE(SUM(d.amount) FILTER (WHERE d.status = 'deposited'), 0) AS deposited_amount
+            FROM buckets b
+            LEFT JOIN deposits d
+                ON d.created_at >= b.bucket_start AND d.created_at < b.bucket_end
+            GROUP BY b.bucket_start, d.token
+            ORDER BY b.bucket_start ASC
+            "#,
+        )
+        .bind(interval.as_str())
+        .bind(buckets)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(totals)
+    }
+}
@@ -0,0 +1,281 @@
+use eyre::Result;
+use serde_json::Value;
+
+use crate::{
+    orderbook::OrderbookProvider,
+    primitives::{WebhookDelivery, WebhookDeliveryStatus, WebhookSubscription},
+};
+
+/// A delivery claimed by the dispatcher, with everything needed to send it
+#[derive(Debug, sqlx::FromRow)]
+pub struct PendingWebhookDelivery {
+    pub delivery_id: i64,
+    pub event: String,
+    /// Payload snapshotted when the delivery was enqueued
+    pub payload: Value,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+impl OrderbookProvider {
+    /// Creates a new webhook subscription
+    ///
+    /// # Arguments
+    /// * `id` - Unique subscription identifier
+    /// * `partner` - Partner that owns the subscription
+    /// * `url` - Endpoint the events are POSTed to
+    /// * `secret` - Secret used to sign payloads
+    /// * `events` - Events delivered to the endpoint
+    ///
+    /// # Returns
+    /// The created subscription
+    pub async fn create_webhook_subscription(
+        &self,
+        id: &str,
+        partner: &str,
+        url: &str,
+        secret: &str,
+        events: &[String],
+    ) -> Result<WebhookSubscription> {
+        let subscription = sqlx::query_as::<_, WebhookSubscription>(
+            r#"
+            INSERT INTO webhook_subscriptions (id, partner, url, secret, events)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, partner, url, events, active, created_at
+            "#,
+        )
+        .bind(id)
+        .bind(partner)
+        .bind(url)
+        .bind(secret)
+        .bind(events)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(subscription)
+    }
+
+    /// Retrieves all webhook subscriptions of a partner
+    pub async fn get_webhook_subscriptions(
+        &self,
+        partner: &str,
+    ) -> Result<Vec<WebhookSubscription>> {
+        let subscriptions = sqlx::query_as::<_, WebhookSubscription>(
+            r#"
+            SELECT id, partner, url, events, active, created_at
+            FROM webhook_subscriptions
+            WHERE partner = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(partner)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(subscriptions)
+    }
+
+    /// Retrieves the most recent deliveries of a partner's subscription
+    ///
+    /// # Arguments
+    /// * `partner` - Partner that owns the subscription
+    /// * `subscription_id` - The subscription ID
+    /// * `status` - Optional delivery status to filter by
+    /// * `limit` - Maximum number of deliveries to return
+    pub async fn get_webhook_deliveries(
+        &self,
+        partner: &str,
+        subscription_id: &str,
+        status: Option<&WebhookDeliveryStatus>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            SELECT
+                d.delivery_id,
+                d.subscription_id,
+                d.event,
+                d.deposit_id,
+                d.payload,
+                d.status,
+                d.attempts,
+                d.next_attempt_at,
+                d.last_error,
+                d.last_status_code,
+                d.created_at,
+                d.delivered_at
+            FROM webhook_deliveries d
+            JOIN webhook_subscriptions s ON s.id = d.subscription_id
+            WHERE s.partner = $1
+                AND d.subscription_id = $2
+                AND ($3::TEXT IS NULL OR d.status = $3)
+            ORDER BY d.delivery_id DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(partner)
+        .bind(subscription_id)
+        .bind(status.map(|status| status.to_string()))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    /// Puts a partner's delivery back in the queue for immediate redelivery
+    ///
+    /// The stored payload is kept so the endpoint receives the same event again.
+    ///
+    /// # Returns
+    /// The updated delivery, or None if the partner has no such delivery
+    pub async fn replay_webhook_delivery(
+        &self,
+        partner: &str,
+        delivery_id: i64,
+    ) -> Result<Option<WebhookDelivery>> {
+        let delivery = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            UPDATE webhook_deliveries d
+            SET status = 'pending', attempts = 0, next_attempt_at = NOW(), last_error = NULL
+            FROM webhook_subscriptions s
+            WHERE s.id = d.subscription_id
+                AND s.partner = $1
+                AND d.delivery_id = $2
+            RETURNING
+                d.delivery_id,
+                d.subscription_id,
+                d.event,
+                d.deposit_id,
+                d.payload,
+                d.status,
+                d.attempts,
+                d.next_attempt_at,
+                d.last_error,
+                d.last_status_code,
+                d.created_at,
+                d.delivered_at
+            "#,
+        )
+        .bind(partner)
+        .bind(delivery_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(delivery)
+    }
+
+    /// Claims pending deliveries that are due
+    ///
+    /// Claimed deliveries have their next attempt pushed back by `lease_secs` so
+    /// that other dispatchers skip them while they are being sent. If the
+    /// dispatcher dies mid-delivery, the delivery is retried once the lease ends.
+    /// Pending deliveries of deactivated subscriptions are never sent: they are
+    /// moved to dead instead.
+    ///
+    /// # Arguments
+    /// * `limit` - Maximum number of deliveries to claim
+    /// * `lease_secs` - How long the claim lasts
+    pub async fn claim_webhook_deliveries(
+        &self,
+        limit: i64,
+        lease_secs: f64,
+    ) -> Result<Vec<PendingWebhookDelivery>> {
+        let deliveries = sqlx::query_as::<_, PendingWebhookDelivery>(
+            r#"
+            WITH retired AS (
+                UPDATE webhook_deliveries d
+                SET status = 'dead', last_error = 'Subscription is inactive'
+                FROM webhook_subscriptions s
+                WHERE s.id = d.subscription_id
+                    AND d.status = 'pending'
+                    AND NOT s.active
+            )
+            UPDATE webhook_deliveries d
+            SET next_attempt_at = NOW() + make_interval(secs => $2)
+            FROM webhook_subscriptions s
+            WHERE s.id = d.subscription_id
+                AND s.active
+                AND d.delivery_id IN (
+                    SELECT w.delivery_id
+                    FROM webhook_deliveries w
+                    JOIN webhook_subscriptions ws ON ws.id = w.subscription_id
+                    WHERE w.status = 'pending' AND w.next_attempt_at <= NOW() AND ws.active
+                    ORDER BY w.next_attempt_at
+                    LIMIT $1
+                    FOR UPDATE OF w SKIP LOCKED
+                )
+            RETURNING
+                d.delivery_id,
+                d.event,
+                d.payload,
+                d.attempts,
+                s.url,
+                s.secret
+            "#,
+        )
+        .bind(limit)
+        .bind(lease_secs)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    /// Records a successful delivery attempt
+    pub async fn mark_webhook_delivered(&self, delivery_id: i64, status_code: i32) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = 'delivered',
+                attempts = attempts + 1,
+                last_error = NULL,
+                last_status_code = $1,
+                delivered_at = NOW()
+            WHERE delivery_id = $2
+            "#,
+        )
+        .bind(status_code)
+        .bind(delivery_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records a failed delivery attempt
+    ///
+    /// # Arguments
+    /// * `delivery_id` - The delivery ID
+    /// * `error` - Description of the failure
+    /// * `status_code` - HTTP status code, if a response was received
+    /// * `next_attempt_at` - When to retry, or None to move the delivery to dead
+    pub async fn mark_webhook_failed(
+        &self,
+        delivery_id: i64,
+        error: &str,
+        status_code: Option<i32>,
+        next_attempt_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = CASE WHEN $1::TIMESTAMPTZ IS NULL THEN 'dead' ELSE 'pending' END,
+                attempts = attempts + 1,
+                next_attempt_at = COALESCE($1, next_attempt_at),
+                last_error = $2,
+                last_status_code = $3
+            WHERE delivery_id = $4
+            "#,
+        )
+        .bind(next_attempt_at)
+        .bind(error)
+        .bind(status_code)
+        .bind(delivery_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
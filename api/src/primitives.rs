@@ -1,12 +1,14 @@
 use axum::{Json, http::StatusCode, response::IntoResponse, response::Response as AxumResponse};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use bigdecimal::BigDecimal;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 /// Status of an API response
 ///
 /// Used to indicate whether an API call was successful or encountered an error.
 /// This is included as a top-level field in every response.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
 pub enum Status {
     /// Operation completed successfully
     Ok,
@@ -24,11 +26,12 @@ pub enum Status {
 /// # Examples
 ///
 /// ```
-/// # use crate::api::primitives::{Response, Status};
+/// # use crate::api::primitives::{ApiErrorCode, Response, Status};
 /// let success = Response::ok("success data");
-/// let error = Response::<()>::error("something went wrong");
+/// let error = Response::<()>::error(ApiErrorCode::Internal, "something went wrong");
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[schema(description = "Standard API response wrapper")]
 pub struct Response<T> {
     /// Status of the response (Ok or Error)
     pub status: Status,
@@ -42,6 +45,10 @@ pub struct Response<T> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
 
+    /// Machine-readable error code when status is Error
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<ApiErrorCode>,
+
     /// The status code of the response
     #[serde(skip)]
     pub status_code: StatusCode,
@@ -62,25 +69,171 @@ impl<T> Response<T> {
             status: Status::Ok,
             result: Some(data),
             error: None,
+            code: None,
             status_code: StatusCode::OK,
         }
     }
 
-    /// Creates an error response with the given error message
+    /// Creates an error response with the given error code and message
     ///
     /// # Arguments
     ///
+    /// * `code` - The error code, which also determines the HTTP status
     /// * `error` - Any type that can be converted to a String
     ///
     /// # Returns
     ///
-    /// A JSON-wrapped Response with Error status and the provided error message
-    pub fn error<E: ToString>(error: E, status_code: StatusCode) -> Self {
+    /// A JSON-wrapped Response with Error status, the code and the error message
+    pub fn error<E: ToString>(code: ApiErrorCode, error: E) -> Self {
         Self {
             status: Status::Error,
             error: Some(error.to_string()),
+            code: Some(code),
             result: None,
-            status_code,
+            status_code: code.status_code(),
+        }
+    }
+
+    /// Creates an error response for an internal failure
+    ///
+    /// The details are logged but not returned, so that raw database or RPC
+    /// messages never reach clients.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The error code, which also determines the HTTP status
+    /// * `error` - The underlying error, only logged
+    pub fn internal_error<E: std::fmt::Display>(code: ApiErrorCode, error: E) -> Self {
+        tracing::error!(code = ?code, "{}: {}", code.message(), error);
+        Self::error(code, code.message())
+    }
+
+    /// Overrides the HTTP status derived from the error code
+    pub fn with_status(mut self, status_code: StatusCode) -> Self {
+        self.status_code = status_code;
+        self
+    }
+}
+
+/// Machine-readable error codes returned in the `code` field of error responses
+///
+/// Clients should branch on the code rather than on the error message, which is
+/// meant for humans and may change.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ApiErrorCode {
+    /// The request is malformed
+    InvalidRequest,
+    /// An address is not a valid hex felt
+    InvalidAddress,
+    /// The deposit amount is zero or negative
+    AmountNotPositive,
+    /// The deposit amount has a fractional part in base units
+    AmountNotInteger,
+    /// The deposit amount does not fit in a u256
+    AmountTooLarge,
+    /// The deposit amount is below the asset's minimum
+    AmountBelowMinimum,
+    /// The deposit token is not a supported asset
+    UnsupportedToken,
+    /// The deposit target is not a Vesu vToken of the deposit token
+    InvalidTarget,
+    /// The deposit action is not known
+    UnknownAction,
+    /// The pagination cursor is invalid
+    InvalidCursor,
+    /// The `Idempotency-Key` header is invalid
+    InvalidIdempotencyKey,
+    /// The webhook URL or events are invalid
+    InvalidWebhook,
+    /// Authentication is missing or invalid
+    Unauthorized,
+    /// The authenticated caller may not access the resource
+    Forbidden,
+    /// The deposit does not exist
+    DepositNotFound,
+    /// The webhook delivery does not exist
+    DeliveryNotFound,
+    /// The idempotency key was used with a different request, or is in use
+    IdempotencyConflict,
+    /// The deposit is no longer `created` or its address holds funds
+    DepositNotCancellable,
+    /// The request body is too large
+    PayloadTooLarge,
+    /// The client exceeded its rate limit
+    RateLimited,
+    /// A database operation failed
+    DbError,
+    /// The Vesu API failed or returned an invalid response
+    UpstreamVesuFailed,
+    /// The requested resource does not exist on Vesu
+    UpstreamVesuNotFound,
+    /// Vesu rejected the request parameters
+    UpstreamVesuRejected,
+    /// The Vesu API did not respond in time
+    UpstreamVesuTimeout,
+    /// The price provider failed
+    UpstreamPriceFailed,
+    /// The Starknet RPC failed or is unreachable
+    RpcUnavailable,
+    /// A dependency required to serve requests is failing
+    NotReady,
+    /// No account is configured to send `recover()` transactions
+    RecoveryDisabled,
+    /// A Starknet transaction could not be sent
+    TransactionFailed,
+    /// An unexpected internal error
+    Internal,
+}
+
+impl ApiErrorCode {
+    /// HTTP status returned with the code
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ApiErrorCode::InvalidRequest
+            | ApiErrorCode::InvalidAddress
+            | ApiErrorCode::AmountNotPositive
+            | ApiErrorCode::AmountNotInteger
+            | ApiErrorCode::AmountTooLarge
+            | ApiErrorCode::AmountBelowMinimum
+            | ApiErrorCode::UnsupportedToken
+            | ApiErrorCode::InvalidTarget
+            | ApiErrorCode::UnknownAction
+            | ApiErrorCode::InvalidCursor
+            | ApiErrorCode::InvalidIdempotencyKey
+            | ApiErrorCode::InvalidWebhook
+            | ApiErrorCode::UpstreamVesuRejected => StatusCode::BAD_REQUEST,
+            ApiErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ApiErrorCode::DepositNotFound
+            | ApiErrorCode::DeliveryNotFound
+            | ApiErrorCode::UpstreamVesuNotFound => StatusCode::NOT_FOUND,
+            ApiErrorCode::IdempotencyConflict | ApiErrorCode::DepositNotCancellable => {
+                StatusCode::CONFLICT
+            }
+            ApiErrorCode::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ApiErrorCode::DbError | ApiErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiErrorCode::UpstreamVesuFailed
+            | ApiErrorCode::UpstreamPriceFailed
+            | ApiErrorCode::TransactionFailed => StatusCode::BAD_GATEWAY,
+            ApiErrorCode::UpstreamVesuTimeout => StatusCode::GATEWAY_TIMEOUT,
+            ApiErrorCode::RpcUnavailable
+            | ApiErrorCode::NotReady
+            | ApiErrorCode::RecoveryDisabled => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// Generic message returned for internal failures
+    pub fn message(&self) -> &'static str {
+        match self {
+            ApiErrorCode::DbError => "Database error",
+            ApiErrorCode::UpstreamVesuFailed => "Vesu request failed",
+            ApiErrorCode::UpstreamVesuTimeout => "Vesu request timed out",
+            ApiErrorCode::UpstreamPriceFailed => "Price provider failed",
+            ApiErrorCode::RpcUnavailable => "Starknet RPC unavailable",
+            ApiErrorCode::NotReady => "Service is not ready",
+            _ => "Internal error",
         }
     }
 }
@@ -97,11 +250,26 @@ where
     }
 }
 
+/// Body of an error response as it appears on the wire
+///
+/// Only used to describe errors in the OpenAPI document; handlers build errors
+/// with [`Response::error`].
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct ErrorResponse {
+    /// Always `Error`
+    pub status: Status,
+    /// Error details
+    pub error: String,
+    /// Machine-readable error code
+    pub code: ApiErrorCode,
+}
+
 /// Type alias for API result (success or error)
 pub type ApiResult<T> = Result<Response<T>, Response<()>>;
 
 /// Represents a supported asset with its metadata and identifiers.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct Asset {
     /// The display name of the asset (e.g., "Ether").
     pub name: String,
@@ -113,13 +281,353 @@ pub struct Asset {
     pub coingecko_id: String,
     /// The on-chain address of the asset.
     pub address: String,
+    /// The minimum deposit amount in base units.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub min_amount: Option<BigDecimal>,
     /// The price of the asset.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
     pub price: Option<BigDecimal>,
 }
 
+/// Result of checking a single dependency
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DependencyCheck {
+    /// Whether the dependency is healthy
+    pub ok: bool,
+    /// Time taken by the check in milliseconds
+    pub latency_ms: u64,
+    /// Reason the check failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Freshness of the cached price of a supported asset
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PriceFreshness {
+    /// The ticker symbol of the asset
+    pub symbol: String,
+    /// Whether a price younger than the configured maximum age is cached
+    pub fresh: bool,
+    /// Seconds since the price was last refreshed, if it ever was
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_secs: Option<i64>,
+}
+
+/// Breakdown of the readiness checks
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessReport {
+    /// Whether every critical dependency is healthy
+    pub ready: bool,
+    /// Postgres connection pool
+    pub database: DependencyCheck,
+    /// Starknet RPC, including the chain ID check
+    pub starknet_rpc: DependencyCheck,
+    /// Cached prices of the supported assets
+    pub prices: Vec<PriceFreshness>,
+}
+
+/// Token metadata as reported by Vesu
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VesuToken {
+    /// The on-chain address of the token
+    pub address: String,
+    /// The display name of the token
+    pub name: String,
+    /// The ticker symbol of the token
+    pub symbol: String,
+    /// The number of decimals the token uses
+    pub decimals: u32,
+}
+
+/// Market statistics of an asset in a Vesu pool
+///
+/// Amounts are in token units and rates are fractions, e.g. `0.05` for 5%.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VesuAssetStats {
+    /// Total amount supplied to the pool
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub total_supplied: Option<BigDecimal>,
+    /// Total amount borrowed from the pool
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub total_borrowed: Option<BigDecimal>,
+    /// Share of the supplied amount that is borrowed
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub utilization: Option<BigDecimal>,
+    /// Yearly yield earned by suppliers
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub supply_apy: Option<BigDecimal>,
+    /// Additional yearly reward rate paid to suppliers
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub reward_apr: Option<BigDecimal>,
+    /// Yearly rate paid by borrowers
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub borrow_apr: Option<BigDecimal>,
+}
+
+/// An asset listed in a Vesu pool
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VesuPoolAsset {
+    /// The listed token
+    pub token: VesuToken,
+    /// The vToken (ERC-4626 vault) issued for supplying the token
+    pub v_token: Option<VesuToken>,
+    /// The USD price of one token
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub usd_price: Option<BigDecimal>,
+    /// Market statistics, if Vesu reports them
+    pub stats: Option<VesuAssetStats>,
+}
+
+/// A Vesu lending pool
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VesuPool {
+    /// Vesu pool identifier
+    pub id: String,
+    /// The on-chain address of the pool, if deployed as its own contract
+    pub address: Option<String>,
+    /// The display name of the pool
+    pub name: String,
+    /// Whether Vesu has verified the pool
+    pub is_verified: bool,
+    /// Assets listed in the pool
+    pub assets: Vec<VesuPoolAsset>,
+}
+
+/// Kind of a Vesu position
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum VesuPositionType {
+    /// Supplied assets earning yield
+    Earn,
+    /// Collateral with outstanding debt
+    Borrow,
+    /// Leveraged position
+    Multiply,
+    /// A position type this API does not know yet
+    #[serde(other)]
+    Unknown,
+}
+
+/// An amount of a token with its USD valuation
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VesuTokenAmount {
+    /// The token
+    pub token: VesuToken,
+    /// Amount in token units
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub amount: BigDecimal,
+    /// The USD price of one token
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub usd_price: Option<BigDecimal>,
+    /// The USD value of the amount
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub usd_value: Option<BigDecimal>,
+}
+
+/// A user's position in a Vesu pool
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VesuPosition {
+    /// Kind of the position
+    #[serde(rename = "type")]
+    pub position_type: VesuPositionType,
+    /// Vesu pool identifier
+    pub pool_id: String,
+    /// The display name of the pool
+    pub pool_name: String,
+    /// Supplied collateral
+    pub collateral: VesuTokenAmount,
+    /// vToken shares held for the collateral
+    pub collateral_shares: Option<VesuTokenAmount>,
+    /// Outstanding debt
+    pub debt: Option<VesuTokenAmount>,
+    /// Whether the pool is deprecated
+    pub is_deprecated: bool,
+}
+
+/// An entry of a user's Vesu transaction history
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VesuHistoryEntry {
+    /// Kind of the transaction, e.g. `deposit` or `withdraw`
+    pub action: String,
+    /// Hash of the transaction
+    pub tx_hash: String,
+    /// Time of the transaction
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Vesu pool identifier
+    pub pool_id: Option<String>,
+    /// The display name of the pool
+    pub pool_name: Option<String>,
+    /// Collateral moved by the transaction
+    pub collateral: Option<VesuTokenAmount>,
+    /// Debt moved by the transaction
+    pub debt: Option<VesuTokenAmount>,
+}
+
+/// A Vesu position valued in USD
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PortfolioPosition {
+    #[serde(flatten)]
+    pub position: VesuPosition,
+    /// Collateral value minus debt value, if every involved token is priced
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub usd_value: Option<BigDecimal>,
+}
+
+/// A deposit that has not reached Vesu yet, valued in USD
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PendingDeposit {
+    #[serde(flatten)]
+    pub deposit: DepositResponse,
+    /// Vesu pool the deposit targets, if its target is a known vToken
+    pub pool_id: Option<String>,
+    /// Amount in token units, if the token is a supported asset
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub token_amount: Option<BigDecimal>,
+    /// The USD value of the amount, if the token is priced
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub usd_value: Option<BigDecimal>,
+}
+
+/// USD totals of a user's holdings in one Vesu pool
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PortfolioPool {
+    /// Vesu pool identifier
+    pub pool_id: String,
+    /// The display name of the pool
+    pub pool_name: Option<String>,
+    /// Number of positions in the pool
+    pub positions: usize,
+    /// Total value of the positions in the pool
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub usd_value: BigDecimal,
+    /// Total value of pending deposits into the pool
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub pending_usd_value: BigDecimal,
+}
+
+/// A user's OneSat deposits and Vesu positions valued in USD
+///
+/// Totals only include positions and deposits that could be priced.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Portfolio {
+    /// The user's wallet address
+    pub user_address: String,
+    /// Total value of the Vesu positions
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub total_usd_value: BigDecimal,
+    /// Total value of deposits that are not `deposited` yet
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub pending_usd_value: BigDecimal,
+    /// Vesu positions
+    pub positions: Vec<PortfolioPosition>,
+    /// Deposits that are not `deposited` yet
+    pub pending_deposits: Vec<PendingDeposit>,
+    /// Totals per Vesu pool
+    pub pools: Vec<PortfolioPool>,
+}
+
+/// Query parameters of `GET /quote`
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct QuoteQuery {
+    /// Symbol or address of the supported asset paid in, e.g. `BTC`
+    pub source_asset: String,
+    /// Amount paid in, in token units of the source asset
+    #[param(value_type = String)]
+    pub amount: BigDecimal,
+    /// Address of the token supplied to Vesu
+    pub target_token: String,
+    /// Vesu pool identifier
+    pub pool_id: String,
+}
+
+/// Estimated fee the relayer pays to deploy a vault
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeployFee {
+    /// Fee in units of the fee token
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub amount: BigDecimal,
+    /// Fee token symbol, `STRK` or `ETH`
+    pub token: String,
+    /// Number of recent deployments the estimate is averaged over
+    pub samples: usize,
+    /// The USD value of the fee, if the fee token is priced
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub usd_value: Option<BigDecimal>,
+}
+
+/// Expected outcome of depositing into a Vesu pool
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Quote {
+    /// Symbol of the asset paid in
+    pub source_asset: String,
+    /// Amount paid in, in token units
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub source_amount: BigDecimal,
+    /// The USD price of the source asset
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub source_price: BigDecimal,
+    /// Address of the token supplied to Vesu
+    pub target_token: String,
+    /// Symbol of the token supplied to Vesu
+    pub target_symbol: String,
+    /// The USD price of the target token
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub target_price: BigDecimal,
+    /// Expected amount of the target token, in token units
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub token_amount: BigDecimal,
+    /// Expected amount of the target token in base units, as passed to `POST /deposit`
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub token_amount_base_units: BigDecimal,
+    /// Vesu pool identifier
+    pub pool_id: String,
+    /// Address of the pool's vToken for the target token
+    pub v_token: String,
+    /// Expected vToken shares, in token units of the vToken
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub shares: BigDecimal,
+    /// Current yearly yield earned by suppliers, as a fraction
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub supply_apy: Option<BigDecimal>,
+    /// Current additional yearly reward rate, as a fraction
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub reward_apr: Option<BigDecimal>,
+    /// Estimated relayer fee for deploying the vault
+    pub deploy_fee: Option<DeployFee>,
+}
+
 /// Status of a deposit operation
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "text", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum DepositStatus {
@@ -129,6 +637,8 @@ pub enum DepositStatus {
     Initiated,
     /// Deposit has been confirmed on-chain
     Deposited,
+    /// Deposit was abandoned by its owner before being funded
+    Cancelled,
 }
 
 impl std::fmt::Display for DepositStatus {
@@ -137,27 +647,57 @@ impl std::fmt::Display for DepositStatus {
             DepositStatus::Created => write!(f, "created"),
             DepositStatus::Initiated => write!(f, "initiated"),
             DepositStatus::Deposited => write!(f, "deposited"),
+            DepositStatus::Cancelled => write!(f, "cancelled"),
         }
     }
 }
 
+/// Action that deposits the funds into the Vesu vToken given as target
+pub const ACTION_VESU_DEPOSIT: u128 = 1;
+
+/// Actions the deposit vault executes when it is deployed
+pub const KNOWN_ACTIONS: [u128; 1] = [ACTION_VESU_DEPOSIT];
+
 /// Request to create a new deposit
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateDepositRequest {
     /// User's wallet address (hex string)
     pub user_address: String,
-    /// Action type identifier
+    /// Action type identifier, `1` to deposit into Vesu
     pub action: u128,
-    /// Deposit amount
+    /// Deposit amount as an integer in the token's base units
+    #[schema(value_type = String)]
     pub amount: BigDecimal,
-    /// Token contract address (hex string)
+    /// Token contract address of a supported asset (hex string)
     pub token: String,
-    /// Target address for the deposit (hex string)
+    /// Vesu vToken of the deposit token the funds are deposited into (hex string)
     pub target_address: String,
 }
 
+/// Maximum number of deposits created by one batch request
+pub const MAX_BATCH_DEPOSITS: usize = 50;
+
+/// Request to create several deposits at once
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateDepositBatchRequest {
+    /// Deposits to create, at most [`MAX_BATCH_DEPOSITS`]
+    pub deposits: Vec<CreateDepositRequest>,
+}
+
+/// Outcome of a batch deposit creation
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DepositBatchResponse {
+    /// Number of deposits created
+    pub created: usize,
+    /// Number of deposits rejected
+    pub rejected: usize,
+    /// One result per requested deposit, in request order: the created deposit,
+    /// or the error that rejected it
+    pub results: Vec<Response<DepositResponse>>,
+}
+
 /// Response containing deposit details
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct DepositResponse {
     /// Unique deposit identifier (32 bytes as hex string)
     pub deposit_id: String,
@@ -168,6 +708,7 @@ pub struct DepositResponse {
     pub action: u128,
     /// Deposit amount
     #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
     pub amount: BigDecimal,
     /// Token contract address
     pub token: String,
@@ -186,6 +727,263 @@ pub struct DepositResponse {
     pub atomiq_swap_id: Option<String>,
 }
 
+/// Recomputation of a deposit address from the deposit's parameters
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DepositVerification {
+    /// Unique deposit identifier (32 bytes as hex string)
+    pub deposit_id: String,
+    /// Deposit address stored when the deposit was created
+    pub deposit_address: String,
+    /// Address of the registry deploying the deposit vault
+    pub registry_address: String,
+    /// Class hash of the deposit vaults, read from the registry
+    pub class_hash: String,
+    /// Pedersen hash of the deposit parameters, used as deployment salt
+    pub salt: String,
+    /// Constructor calldata of the deposit vault
+    pub constructor_calldata: Vec<String>,
+    /// Address computed locally from the values above
+    pub computed_address: String,
+    /// Address returned by the registry's `predict_address`
+    pub predicted_address: String,
+    /// Whether the stored, computed and predicted addresses are all equal
+    pub matches: bool,
+}
+
+/// Default number of buckets returned by `GET /stats/timeseries`
+pub const DEFAULT_STATS_BUCKETS: i64 = 30;
+
+/// Maximum number of buckets returned by `GET /stats/timeseries`
+pub const MAX_STATS_BUCKETS: i64 = 366;
+
+/// Deposited volume of one token
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenVolume {
+    /// Token contract address
+    pub token: String,
+    /// Symbol of the token, if it is a supported asset
+    pub symbol: Option<String>,
+    /// Number of deposited deposits
+    pub deposits: i64,
+    /// Deposited amount in base units
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub amount: BigDecimal,
+    /// Deposited amount in token units, if the token is a supported asset
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub token_amount: Option<BigDecimal>,
+    /// The USD value of the amount at the current price, if the token is priced
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub usd_value: Option<BigDecimal>,
+}
+
+/// Number of deposits in each status
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct DepositStatusCounts {
+    pub created: i64,
+    pub initiated: i64,
+    pub deposited: i64,
+    pub cancelled: i64,
+}
+
+/// Volume deposited through OneSat
+///
+/// Only deposits in the `deposited` status count towards the volume.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DepositStats {
+    /// Number of deposits in any status
+    pub total_deposits: i64,
+    /// Number of deposits in each status
+    pub status_counts: DepositStatusCounts,
+    /// Number of distinct users that created a deposit
+    pub unique_users: i64,
+    /// Total value of the deposited volume at current prices, excluding tokens without a price
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub tvl_usd: BigDecimal,
+    /// Deposited volume per token, largest USD value first
+    pub volume: Vec<TokenVolume>,
+    /// When the aggregates were read from the database
+    pub as_of: chrono::DateTime<chrono::Utc>,
+}
+
+/// Length of the buckets of `GET /stats/timeseries`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsInterval {
+    Hour,
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl StatsInterval {
+    /// The interval as a Postgres `date_trunc` field
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatsInterval::Hour => "hour",
+            StatsInterval::Day => "day",
+            StatsInterval::Week => "week",
+            StatsInterval::Month => "month",
+        }
+    }
+}
+
+/// Query parameters of `GET /stats/timeseries`
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsTimeseriesQuery {
+    /// Length of a bucket (defaults to `day`)
+    pub interval: Option<StatsInterval>,
+    /// Number of most recent buckets to return (defaults to 30, capped at 366)
+    pub limit: Option<i64>,
+}
+
+/// Deposits created during one time bucket
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StatsBucket {
+    /// Start of the bucket, at a UTC boundary
+    pub start: chrono::DateTime<chrono::Utc>,
+    /// Number of deposits created
+    pub deposits: i64,
+    /// Number of those deposits that are deposited
+    pub deposited: i64,
+    /// Value of their deposited volume at current prices, excluding tokens without a price
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub usd_volume: BigDecimal,
+    /// Deposited volume per token
+    pub volume: Vec<TokenVolume>,
+}
+
+/// Deposits aggregated over time
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StatsTimeseries {
+    /// Length of the buckets
+    pub interval: StatsInterval,
+    /// Buckets, oldest first, including buckets without deposits
+    pub buckets: Vec<StatsBucket>,
+    /// When the aggregates were read from the database
+    pub as_of: chrono::DateTime<chrono::Utc>,
+}
+
+/// Deposit lifecycle events partners can subscribe to
+pub const WEBHOOK_EVENTS: [&str; 4] = [
+    "deposit.created",
+    "deposit.initiated",
+    "deposit.deposited",
+    "deposit.cancelled",
+];
+
+/// Request to cancel an unfunded deposit
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CancelDepositRequest {
+    /// Optional reason given by the user, logged with the cancellation
+    pub reason: Option<String>,
+}
+
+/// Request to register a webhook subscription
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    /// HTTPS endpoint the events are POSTed to
+    pub url: String,
+    /// Events to deliver, e.g. `["deposit.deposited"]`
+    pub events: Vec<String>,
+    /// Secret used to sign payloads; generated when omitted
+    pub secret: Option<String>,
+}
+
+/// A registered webhook subscription
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct WebhookSubscription {
+    /// Unique subscription identifier
+    pub id: String,
+    /// Partner that owns the subscription
+    pub partner: String,
+    /// Endpoint the events are POSTed to
+    pub url: String,
+    /// Events delivered to the endpoint
+    pub events: Vec<String>,
+    /// Whether events are currently delivered
+    pub active: bool,
+    /// Timestamp when the subscription was created
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A newly registered webhook subscription, including its signing secret
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedWebhookSubscription {
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+    /// Secret used to sign payloads; only returned on registration
+    pub secret: String,
+}
+
+/// Status of a webhook delivery
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its next delivery attempt
+    Pending,
+    /// Acknowledged with a 2xx response
+    Delivered,
+    /// Gave up after the maximum number of attempts
+    Dead,
+}
+
+impl std::fmt::Display for WebhookDeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WebhookDeliveryStatus::Pending => write!(f, "pending"),
+            WebhookDeliveryStatus::Delivered => write!(f, "delivered"),
+            WebhookDeliveryStatus::Dead => write!(f, "dead"),
+        }
+    }
+}
+
+/// A delivery of a deposit event to a webhook subscription
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct WebhookDelivery {
+    /// Unique delivery identifier
+    pub delivery_id: i64,
+    /// Subscription the delivery belongs to
+    pub subscription_id: String,
+    /// Event name, e.g. `deposit.deposited`
+    pub event: String,
+    /// Deposit the event is about
+    pub deposit_id: String,
+    /// Payload sent to the endpoint, snapshotted when the delivery was enqueued
+    pub payload: serde_json::Value,
+    /// Current delivery status
+    pub status: WebhookDeliveryStatus,
+    /// Number of attempts made so far
+    pub attempts: i32,
+    /// Time of the next attempt while pending
+    pub next_attempt_at: chrono::DateTime<chrono::Utc>,
+    /// Error of the last failed attempt
+    pub last_error: Option<String>,
+    /// HTTP status code of the last attempt
+    pub last_status_code: Option<i32>,
+    /// Timestamp when the delivery was enqueued
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Timestamp when the delivery succeeded
+    pub delivered_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Query parameters accepted by the webhook delivery listing
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WebhookDeliveryQuery {
+    /// Only return deliveries with this status
+    pub status: Option<WebhookDeliveryStatus>,
+    /// Maximum number of deliveries to return (defaults to 50, capped at 200)
+    pub limit: Option<i64>,
+}
+
 /// Custom serializer for BigDecimal to ensure it's serialized as a plain string
 fn serialize_bigdecimal_as_string<S>(value: &BigDecimal, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -193,3 +991,276 @@ where
 {
     serializer.serialize_str(&value.to_string())
 }
+
+/// Custom serializer for optional BigDecimal values, serialized as plain strings or null
+fn serialize_option_bigdecimal_as_string<S>(
+    value: &Option<BigDecimal>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match value {
+        Some(value) => serializer.serialize_str(&value.to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Default number of deposits returned per page
+pub const DEFAULT_PAGE_LIMIT: i64 = 50;
+
+/// Maximum number of deposits a client may request per page
+pub const MAX_PAGE_LIMIT: i64 = 200;
+
+/// Query parameters accepted by the deposit listing endpoints
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DepositListQuery {
+    /// Opaque cursor returned as `next_cursor` by a previous page
+    pub cursor: Option<String>,
+    /// Maximum number of deposits to return (defaults to 50, capped at 200)
+    pub limit: Option<i64>,
+    /// Only return deposits with this status
+    pub status: Option<DepositStatus>,
+    /// Only return deposits for this token contract address
+    pub token: Option<String>,
+    /// Only return deposits with this action type identifier
+    pub action: Option<u128>,
+    /// Only return deposits for this target address
+    pub target_address: Option<String>,
+    /// Only return deposits created at or after this time (RFC 3339)
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only return deposits created strictly before this time (RFC 3339)
+    pub created_before: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Filters applied when listing deposits from the orderbook
+#[derive(Debug, Default, Clone)]
+pub struct DepositFilter {
+    pub user_address: Option<String>,
+    pub status: Option<DepositStatus>,
+    pub token: Option<String>,
+    pub action: Option<u128>,
+    pub target_address: Option<String>,
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_before: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Position in the deposit listing, keyed on `(created_at, deposit_id)`
+///
+/// Deposits are listed newest first, so a cursor points at the last deposit
+/// of the previous page and the next page starts strictly after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositCursor {
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub deposit_id: String,
+}
+
+impl DepositCursor {
+    /// Encodes the cursor as an opaque URL-safe string
+    pub fn encode(&self) -> String {
+        let raw = format!("{}:{}", self.created_at.timestamp_micros(), self.deposit_id);
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    /// Decodes a cursor previously produced by [`DepositCursor::encode`]
+    ///
+    /// # Returns
+    /// `None` if the cursor is malformed
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let raw = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+        let raw = String::from_utf8(raw).ok()?;
+        let (micros, deposit_id) = raw.split_once(':')?;
+        let created_at = chrono::DateTime::from_timestamp_micros(micros.parse().ok()?)?;
+        Some(Self {
+            created_at,
+            deposit_id: deposit_id.to_string(),
+        })
+    }
+}
+
+/// A single page of deposits
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DepositPage {
+    /// Deposits in this page, newest first
+    pub deposits: Vec<DepositResponse>,
+    /// Cursor for the next page, absent when this is the last page
+    pub next_cursor: Option<String>,
+}
+
+/// File format of a deposit history export
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Comma separated values with a header row
+    #[default]
+    Csv,
+    /// A JSON array of rows
+    Json,
+}
+
+/// Query parameters accepted by the deposit history export
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DepositExportQuery {
+    /// Export format, `csv` (default) or `json`
+    pub format: Option<ExportFormat>,
+    /// Only export deposits created at or after this time (RFC 3339)
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only export deposits created strictly before this time (RFC 3339)
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// A row of a deposit history export
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DepositExportRow {
+    /// Timestamp when the deposit was created
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Unique deposit identifier
+    pub deposit_id: String,
+    /// Current status of the deposit
+    pub status: DepositStatus,
+    /// Symbol of the token, absent if it is not a supported asset
+    pub symbol: Option<String>,
+    /// Token contract address
+    pub token: String,
+    /// Amount in token units, absent if the token is not a supported asset
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub amount: Option<BigDecimal>,
+    /// Amount in base units, as stored on the deposit
+    #[serde(serialize_with = "serialize_bigdecimal_as_string")]
+    #[schema(value_type = String)]
+    pub amount_base_units: BigDecimal,
+    /// USD value at the token price when the deposit was created
+    #[serde(serialize_with = "serialize_option_bigdecimal_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub usd_value: Option<BigDecimal>,
+    /// Action type identifier
+    pub action: u128,
+    /// Target address for the deposit
+    pub target_address: String,
+    /// Generated deposit address from registry
+    pub deposit_address: String,
+    /// Transaction hash of the deposit
+    pub deposit_tx_hash: Option<String>,
+    /// Atomiq swap id
+    pub atomiq_swap_id: Option<String>,
+}
+
+/// Query parameters accepted by the admin deposit listing, besides [`DepositListQuery`]
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AdminDepositQuery {
+    /// Only return deposits of this user
+    pub user_address: Option<String>,
+}
+
+/// Request to force a deposit into a status
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ForceDepositStatusRequest {
+    /// Status to move the deposit to
+    pub status: DepositStatus,
+    /// Why the operator is taking the action, recorded in the audit trail
+    pub reason: String,
+}
+
+/// Request body of admin actions that take no parameters besides a reason
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AdminActionRequest {
+    /// Why the operator is taking the action, recorded in the audit trail
+    pub reason: String,
+}
+
+/// Administrative action recorded in the deposit audit trail
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// The status was overwritten by an operator
+    ForceStatus,
+    /// The deposit was handed back to the vault watcher
+    Requeue,
+    /// `recover()` was sent to the deposit address
+    Recover,
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditAction::ForceStatus => write!(f, "force_status"),
+            AuditAction::Requeue => write!(f, "requeue"),
+            AuditAction::Recover => write!(f, "recover"),
+        }
+    }
+}
+
+/// An administrative action taken on a deposit
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct DepositAuditEntry {
+    /// Unique entry identifier
+    pub id: i64,
+    /// Deposit the action was taken on
+    pub deposit_id: String,
+    /// Operator whose admin token authorised the action
+    pub operator: String,
+    /// The action taken
+    pub action: AuditAction,
+    /// Status before the action, for status changes
+    pub from_status: Option<DepositStatus>,
+    /// Status after the action, for status changes
+    pub to_status: Option<DepositStatus>,
+    /// Reason given by the operator
+    pub reason: String,
+    /// Transaction sent by the action, if any
+    pub tx_hash: Option<String>,
+    /// Timestamp when the action was taken
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Result of sending `recover()` to a deposit address
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecoverResponse {
+    /// Hash of the `recover()` invoke transaction
+    pub tx_hash: String,
+    /// Audit entry recording the action
+    pub audit: DepositAuditEntry,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_response_carries_code() {
+        let response = Response::<()>::error(ApiErrorCode::DepositNotFound, "Deposit not found");
+        assert_eq!(response.status_code, StatusCode::NOT_FOUND);
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({
+                "status": "Error",
+                "error": "Deposit not found",
+                "code": "DEPOSIT_NOT_FOUND"
+            })
+        );
+    }
+
+    #[test]
+    fn test_deposit_cursor_roundtrip() {
+        let cursor = DepositCursor {
+            created_at: chrono::DateTime::from_timestamp_micros(1_736_980_042_123_456).unwrap(),
+            deposit_id: "ab".repeat(32),
+        };
+        let encoded = cursor.encode();
+        assert_eq!(DepositCursor::decode(&encoded), Some(cursor));
+    }
+
+    #[test]
+    fn test_deposit_cursor_rejects_garbage() {
+        assert_eq!(DepositCursor::decode("not a cursor"), None);
+        assert_eq!(
+            DepositCursor::decode(&URL_SAFE_NO_PAD.encode("abc:def")),
+            None
+        );
+    }
+}
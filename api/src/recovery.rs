@@ -0,0 +1,99 @@
+use std::{sync::Arc, time::Instant};
+
+use starknet::{
+    accounts::{Account, ExecutionEncoding, SingleOwnerAccount},
+    core::{
+        types::{Call, Felt},
+        utils::get_selector_from_name,
+    },
+    providers::{JsonRpcClient, jsonrpc::HttpTransport},
+    signers::LocalWallet,
+};
+use tracing::Instrument;
+
+use crate::{
+    chain_id::ChainId,
+    metrics::{METRICS, UPSTREAM_STARKNET_RPC, upstream_span},
+    registry::StarknetError,
+};
+
+/// Sends `recover()` to user deposit addresses
+///
+/// `recover` transfers the whole token balance of a deployed deposit vault back
+/// to its user. The contract accepts the call from any account, so the account
+/// configured here only pays the fee.
+pub struct RecoveryAccount {
+    provider: JsonRpcClient<HttpTransport>,
+    signer: LocalWallet,
+    address: Felt,
+    chain_id: Arc<ChainId>,
+}
+
+impl RecoveryAccount {
+    /// Creates a new RecoveryAccount instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - Starknet RPC provider the transactions are sent through
+    /// * `signer` - Key of the account
+    /// * `address` - Account that signs and pays for the transactions
+    /// * `chain_id` - Chain ID the transactions are signed for
+    pub fn new(
+        provider: JsonRpcClient<HttpTransport>,
+        signer: LocalWallet,
+        address: Felt,
+        chain_id: Arc<ChainId>,
+    ) -> Self {
+        Self {
+            provider,
+            signer,
+            address,
+            chain_id,
+        }
+    }
+
+    /// Invokes `recover()` on a deposit vault
+    ///
+    /// # Arguments
+    /// * `deposit_address` - Address of the deployed deposit vault
+    ///
+    /// # Returns
+    /// The hash of the sent transaction
+    pub async fn recover(&self, deposit_address: Felt) -> Result<Felt, StarknetError> {
+        let call = Call {
+            to: deposit_address,
+            selector: get_selector_from_name("recover").map_err(|e| {
+                StarknetError::ContractCallFailed(format!("Invalid selector: {}", e))
+            })?,
+            calldata: vec![],
+        };
+
+        let chain_id = self
+            .chain_id
+            .get()
+            .await
+            .map_err(|e| StarknetError::ProviderError(e.to_string()))?;
+        let account = SingleOwnerAccount::new(
+            self.provider.clone(),
+            self.signer.clone(),
+            self.address,
+            chain_id,
+            ExecutionEncoding::New,
+        );
+
+        let started = Instant::now();
+        let result = account
+            .execute_v3(vec![call])
+            .gas_estimate_multiplier(3.0)
+            .gas_price_estimate_multiplier(3.0)
+            .send()
+            .instrument(upstream_span(UPSTREAM_STARKNET_RPC, "recover"))
+            .await;
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        METRICS.observe_upstream(UPSTREAM_STARKNET_RPC, "recover", outcome, started);
+
+        result
+            .map(|result| result.transaction_hash)
+            .map_err(|e| StarknetError::TransactionFailed(e.to_string()))
+    }
+}
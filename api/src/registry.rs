@@ -1,14 +1,22 @@
+use std::time::Instant;
+
 use bigdecimal::{BigDecimal, num_bigint};
 use eyre::Result;
 use starknet::{
     core::{
-        types::{BlockId, BlockTag, Felt, FunctionCall},
-        utils::get_selector_from_name,
+        crypto::{compute_hash_on_elements, pedersen_hash},
+        types::{BlockId, BlockTag, Felt, FunctionCall, requests::CallRequest},
+        utils::{get_selector_from_name, get_storage_var_address},
+    },
+    providers::{
+        JsonRpcClient, Provider, ProviderRequestData, ProviderResponseData, jsonrpc::HttpTransport,
     },
-    providers::{JsonRpcClient, Provider, jsonrpc::HttpTransport},
 };
 
 use thiserror::Error;
+use tracing::Instrument;
+
+use crate::metrics::{METRICS, UPSTREAM_STARKNET_RPC, upstream_span};
 
 /// Custom error types for Starknet interactions
 ///
@@ -29,11 +37,90 @@ pub enum StarknetError {
     #[error("Provider error: {0}")]
     ProviderError(String),
 
+    /// Error when a transaction cannot be estimated or sent
+    #[error("Transaction failed: {0}")]
+    TransactionFailed(String),
+
     /// Catches any other error types not explicitly handled above
     #[error(transparent)]
     Other(#[from] eyre::Error),
 }
 
+/// `'STARKNET_CONTRACT_ADDRESS'`, the prefix of the contract address hash
+const CONTRACT_ADDRESS_PREFIX: Felt =
+    Felt::from_hex_unchecked("0x535441524b4e45545f434f4e54524143545f41444452455353");
+
+/// Parameters of a deposit whose address is predicted
+pub struct AddressPrediction {
+    pub user: Felt,
+    pub deposit_id: Felt,
+    pub action: u128,
+    pub amount: BigDecimal,
+    pub token: Felt,
+    pub target: Felt,
+}
+
+impl AddressPrediction {
+    /// Builds the calldata of `predict_address`
+    fn calldata(&self) -> Result<Vec<Felt>, StarknetError> {
+        let (amount_low, amount_high) = bigdecimal_to_i128s(&self.amount)?;
+        Ok(vec![
+            self.user,
+            self.deposit_id,
+            Felt::from(self.action),
+            Felt::from(amount_low),
+            Felt::from(amount_high),
+            self.token,
+            self.target,
+        ])
+    }
+
+    /// Computes the deposit address locally, the way the registry contract does
+    ///
+    /// # Arguments
+    /// * `registry` - The address of the registry deploying the vault
+    /// * `class_hash` - The class hash of the deposit vaults
+    ///
+    /// # Returns
+    /// The salt, constructor calldata and resulting deposit address
+    pub fn compute_address(
+        &self,
+        registry: Felt,
+        class_hash: Felt,
+    ) -> Result<ComputedAddress, StarknetError> {
+        // The salt hashes the `predict_address` arguments without appending
+        // their length, unlike `compute_hash_on_elements`
+        let calldata = self.calldata()?;
+        let salt = calldata
+            .iter()
+            .fold(Felt::ZERO, |state, value| pedersen_hash(&state, value));
+
+        // The constructor takes the same arguments except the deposit ID
+        let constructor_calldata = [&calldata[..1], &calldata[2..]].concat();
+        let calldata_hash = compute_hash_on_elements(&constructor_calldata);
+        let address = compute_hash_on_elements(&[
+            CONTRACT_ADDRESS_PREFIX,
+            registry,
+            salt,
+            class_hash,
+            calldata_hash,
+        ]);
+
+        Ok(ComputedAddress {
+            salt,
+            constructor_calldata,
+            address,
+        })
+    }
+}
+
+/// Deposit address computed locally, with the inputs of its computation
+pub struct ComputedAddress {
+    pub salt: Felt,
+    pub constructor_calldata: Vec<Felt>,
+    pub address: Felt,
+}
+
 #[derive(Clone)]
 pub struct VaultRegistry {
     pub address: Felt,
@@ -68,10 +155,36 @@ impl VaultRegistry {
             })?,
         };
 
-        self.provider
+        let started = Instant::now();
+        let result = self
+            .provider
             .call(execution_call, BlockId::Tag(BlockTag::Latest))
-            .await
-            .map_err(|e| StarknetError::ProviderError(e.to_string()))
+            .instrument(upstream_span(UPSTREAM_STARKNET_RPC, selector))
+            .await;
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        METRICS.observe_upstream(UPSTREAM_STARKNET_RPC, selector, outcome, started);
+
+        result.map_err(|e| StarknetError::ProviderError(e.to_string()))
+    }
+
+    /// Reads the class hash of the deposit vaults deployed by the registry
+    ///
+    /// # Returns
+    /// The class hash stored in the `uda_class_hash` storage variable
+    pub async fn uda_class_hash(&self) -> Result<Felt, StarknetError> {
+        let key = get_storage_var_address("uda_class_hash", &[])
+            .map_err(|e| StarknetError::Other(e.into()))?;
+
+        let started = Instant::now();
+        let result = self
+            .provider
+            .get_storage_at(self.address, key, BlockId::Tag(BlockTag::Latest))
+            .instrument(upstream_span(UPSTREAM_STARKNET_RPC, "uda_class_hash"))
+            .await;
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        METRICS.observe_upstream(UPSTREAM_STARKNET_RPC, "uda_class_hash", outcome, started);
+
+        result.map_err(|e| StarknetError::ProviderError(e.to_string()))
     }
 
     /// Predicts the deposit address for a given set of parameters
@@ -94,23 +207,95 @@ impl VaultRegistry {
         token: &Felt,
         target: &Felt,
     ) -> Result<Felt, StarknetError> {
-        let (amount_low, amount_high) = bigdecimal_to_i128s(amount)?;
-        let calldata = vec![
-            user.clone(),
-            deposit_id.clone(),
-            Felt::from(action),
-            Felt::from(amount_low),
-            Felt::from(amount_high),
-            token.clone(),
-            target.clone(),
-        ];
+        let calldata = AddressPrediction {
+            user: *user,
+            deposit_id: *deposit_id,
+            action,
+            amount: amount.clone(),
+            token: *token,
+            target: *target,
+        }
+        .calldata()?;
         let result = self.call_contract("predict_address", calldata).await?;
 
-        // The contract should return the predicted address as the first element
-        result.first().cloned().ok_or_else(|| {
-            StarknetError::InvalidResponse("Empty response from predict_address".to_string())
-        })
+        predicted_address(&result)
     }
+
+    /// Predicts the deposit addresses of several deposits in one batched RPC request
+    ///
+    /// # Arguments
+    /// * `deposits` - The deposits to predict the addresses of
+    ///
+    /// # Returns
+    /// The predicted deposit addresses, in the order of `deposits`. Fails as a
+    /// whole if any of the calls fails.
+    pub async fn predict_addresses(
+        &self,
+        deposits: &[AddressPrediction],
+    ) -> Result<Vec<Felt>, StarknetError> {
+        if deposits.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let selector = get_selector_from_name("predict_address")
+            .map_err(|e| StarknetError::ContractCallFailed(format!("Invalid selector: {}", e)))?;
+        let requests = deposits
+            .iter()
+            .map(|deposit| {
+                Ok(ProviderRequestData::Call(CallRequest {
+                    request: FunctionCall {
+                        contract_address: self.address,
+                        entry_point_selector: selector,
+                        calldata: deposit.calldata()?,
+                    },
+                    block_id: BlockId::Tag(BlockTag::Latest),
+                }))
+            })
+            .collect::<Result<Vec<_>, StarknetError>>()?;
+
+        let started = Instant::now();
+        let result = self
+            .provider
+            .batch_requests(&requests)
+            .instrument(upstream_span(
+                UPSTREAM_STARKNET_RPC,
+                "predict_address_batch",
+            ))
+            .await;
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        METRICS.observe_upstream(
+            UPSTREAM_STARKNET_RPC,
+            "predict_address_batch",
+            outcome,
+            started,
+        );
+
+        let responses = result.map_err(|e| StarknetError::ProviderError(e.to_string()))?;
+        if responses.len() != deposits.len() {
+            return Err(StarknetError::InvalidResponse(format!(
+                "Expected {} predict_address responses, got {}",
+                deposits.len(),
+                responses.len()
+            )));
+        }
+        responses
+            .into_iter()
+            .map(|response| match response {
+                ProviderResponseData::Call(result) => predicted_address(&result),
+                _ => Err(StarknetError::InvalidResponse(
+                    "Unexpected response to predict_address".to_string(),
+                )),
+            })
+            .collect()
+    }
+}
+
+/// Reads the address returned by `predict_address`
+fn predicted_address(result: &[Felt]) -> Result<Felt, StarknetError> {
+    // The contract should return the predicted address as the first element
+    result.first().cloned().ok_or_else(|| {
+        StarknetError::InvalidResponse("Empty response from predict_address".to_string())
+    })
 }
 
 /// Converts a BigDecimal value into a tuple of two i128 values
@@ -156,3 +341,70 @@ pub fn bigdecimal_to_i128s(value: &BigDecimal) -> Result<(i128, i128)> {
 
     Ok((low, high))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_prediction_calldata_splits_amount() {
+        let amount = (num_bigint::BigInt::from(1) << 128usize) + 5;
+        let prediction = AddressPrediction {
+            user: Felt::from(1u8),
+            deposit_id: Felt::from(2u8),
+            action: 1,
+            amount: BigDecimal::from(amount),
+            token: Felt::from(3u8),
+            target: Felt::from(4u8),
+        };
+
+        assert_eq!(
+            prediction.calldata().unwrap(),
+            vec![
+                Felt::from(1u8),
+                Felt::from(2u8),
+                Felt::from(1u8),
+                Felt::from(5u8),
+                Felt::from(1u8),
+                Felt::from(3u8),
+                Felt::from(4u8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_address_matches_starknet_formula() {
+        let prediction = AddressPrediction {
+            user: Felt::from_hex_unchecked("0x1234"),
+            deposit_id: Felt::from_hex_unchecked("0xabcdef"),
+            action: 1,
+            amount: BigDecimal::from(1_000_000),
+            token: Felt::from_hex_unchecked("0x5678"),
+            target: Felt::from_hex_unchecked("0x9abc"),
+        };
+        let registry = Felt::from_hex_unchecked("0x42");
+        let class_hash = Felt::from_hex_unchecked("0x777");
+
+        let computed = prediction.compute_address(registry, class_hash).unwrap();
+        assert_eq!(
+            computed.constructor_calldata,
+            vec![
+                prediction.user,
+                Felt::from(1u8),
+                Felt::from(1_000_000u32),
+                Felt::ZERO,
+                prediction.token,
+                prediction.target,
+            ]
+        );
+        assert_eq!(
+            computed.address,
+            starknet::core::utils::get_contract_address(
+                computed.salt,
+                class_hash,
+                &computed.constructor_calldata,
+                registry,
+            )
+        );
+    }
+}
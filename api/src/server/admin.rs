@@ -0,0 +1,296 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use starknet::core::types::Felt;
+use tracing::info;
+
+use crate::{
+    primitives::{
+        AdminActionRequest, AdminDepositQuery, ApiErrorCode, ApiResult, AuditAction,
+        DepositAuditEntry, DepositListQuery, DepositPage, DepositResponse, DepositStatus,
+        ErrorResponse, ForceDepositStatusRequest, RecoverResponse, Response,
+    },
+    server::{
+        auth::Operator,
+        handler::{HandlerState, list_deposits},
+    },
+};
+
+/// Maximum length of the reason given for an admin action
+const MAX_REASON_LENGTH: usize = 1000;
+
+/// Lists deposits across all users
+///
+/// # Query Parameters
+/// * `user_address` - Optional user to restrict the listing to
+/// * `cursor`, `limit` and the filters accepted by [`DepositListQuery`]
+#[utoipa::path(
+    get,
+    path = "/admin/deposits",
+    tag = "admin",
+    params(AdminDepositQuery, DepositListQuery),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Page of deposits", body = Response<DepositPage>),
+        (status = 400, description = "Invalid query", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorResponse)
+    )
+)]
+pub async fn admin_list_deposits(
+    State(state): State<Arc<HandlerState>>,
+    Operator(_operator): Operator,
+    Query(admin_query): Query<AdminDepositQuery>,
+    Query(query): Query<DepositListQuery>,
+) -> ApiResult<DepositPage> {
+    list_deposits(&state, admin_query.user_address, query).await
+}
+
+/// Forces a deposit into a status
+///
+/// No transition rules are applied; the change and its reason are recorded in
+/// the deposit's audit trail.
+///
+/// # Path Parameters
+/// * `deposit_id` - The deposit ID
+#[utoipa::path(
+    post,
+    path = "/admin/deposits/{deposit_id}/status",
+    tag = "admin",
+    params(("deposit_id" = String, Path, description = "The deposit ID")),
+    request_body = ForceDepositStatusRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Status updated", body = Response<DepositResponse>),
+        (status = 400, description = "Missing reason or status unchanged", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorResponse),
+        (status = 404, description = "Deposit not found", body = ErrorResponse)
+    )
+)]
+pub async fn admin_force_deposit_status(
+    State(state): State<Arc<HandlerState>>,
+    Operator(operator): Operator,
+    Path(deposit_id): Path<String>,
+    Json(request): Json<ForceDepositStatusRequest>,
+) -> ApiResult<DepositResponse> {
+    let reason = validate_reason(&request.reason)?;
+    let previous = find_deposit(&state, &deposit_id).await?;
+    if previous.status == request.status {
+        return Err(Response::error(
+            ApiErrorCode::InvalidRequest,
+            format!("Deposit is already {}", request.status),
+        ));
+    }
+
+    let deposit = state
+        .orderbook
+        .force_deposit_status(&deposit_id, &request.status, &operator, reason)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?
+        .ok_or_else(deposit_not_found)?;
+
+    info!(
+        operator,
+        deposit_id,
+        from = %previous.status,
+        to = %request.status,
+        reason,
+        "Deposit status forced"
+    );
+    Ok(Response::ok(deposit))
+}
+
+/// Hands a deposit back to the vault watcher
+///
+/// Moves the deposit to `created` and clears its deployment transaction hash,
+/// so the vault watcher picks it up on its next poll.
+///
+/// # Path Parameters
+/// * `deposit_id` - The deposit ID
+#[utoipa::path(
+    post,
+    path = "/admin/deposits/{deposit_id}/requeue",
+    tag = "admin",
+    params(("deposit_id" = String, Path, description = "The deposit ID")),
+    request_body = AdminActionRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Deposit re-queued", body = Response<DepositResponse>),
+        (status = 400, description = "Missing reason or deposit already queued", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorResponse),
+        (status = 404, description = "Deposit not found", body = ErrorResponse)
+    )
+)]
+pub async fn admin_requeue_deposit(
+    State(state): State<Arc<HandlerState>>,
+    Operator(operator): Operator,
+    Path(deposit_id): Path<String>,
+    Json(request): Json<AdminActionRequest>,
+) -> ApiResult<DepositResponse> {
+    let reason = validate_reason(&request.reason)?;
+    let deposit = find_deposit(&state, &deposit_id).await?;
+    if deposit.status == DepositStatus::Created {
+        return Err(Response::error(
+            ApiErrorCode::InvalidRequest,
+            "Deposit is already queued",
+        ));
+    }
+
+    let deposit = state
+        .orderbook
+        .requeue_deposit(&deposit_id, &operator, reason)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?
+        .ok_or_else(deposit_not_found)?;
+
+    info!(operator, deposit_id, reason, "Deposit re-queued");
+    Ok(Response::ok(deposit))
+}
+
+/// Sends `recover()` to a deposit address
+///
+/// Recovery returns the vault's whole token balance to the user. The vault must
+/// have been deployed, otherwise the transaction is rejected.
+///
+/// # Path Parameters
+/// * `deposit_id` - The deposit ID
+#[utoipa::path(
+    post,
+    path = "/admin/deposits/{deposit_id}/recover",
+    tag = "admin",
+    params(("deposit_id" = String, Path, description = "The deposit ID")),
+    request_body = AdminActionRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Recover transaction sent", body = Response<RecoverResponse>),
+        (status = 400, description = "Missing reason", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorResponse),
+        (status = 404, description = "Deposit not found", body = ErrorResponse),
+        (status = 502, description = "Transaction rejected", body = ErrorResponse),
+        (status = 503, description = "Recovery is not configured", body = ErrorResponse)
+    )
+)]
+pub async fn admin_recover_deposit(
+    State(state): State<Arc<HandlerState>>,
+    Operator(operator): Operator,
+    Path(deposit_id): Path<String>,
+    Json(request): Json<AdminActionRequest>,
+) -> ApiResult<RecoverResponse> {
+    let reason = validate_reason(&request.reason)?;
+    let recovery = state.recovery.as_ref().ok_or_else(|| {
+        Response::error(
+            ApiErrorCode::RecoveryDisabled,
+            "No recovery account is configured",
+        )
+    })?;
+    let deposit = find_deposit(&state, &deposit_id).await?;
+    let deposit_address = Felt::from_hex(&deposit.deposit_address)
+        .map_err(|e| Response::internal_error(ApiErrorCode::Internal, e))?;
+
+    let tx_hash = recovery.recover(deposit_address).await.map_err(|e| {
+        Response::error(
+            ApiErrorCode::TransactionFailed,
+            format!("recover() failed: {}", e),
+        )
+    })?;
+    let tx_hash = format!("{:#x}", tx_hash);
+    info!(
+        operator,
+        deposit_id, tx_hash, reason, "Deposit recovery sent"
+    );
+
+    let audit = state
+        .orderbook
+        .record_deposit_audit(
+            &deposit_id,
+            &operator,
+            AuditAction::Recover,
+            reason,
+            Some(&tx_hash),
+        )
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?;
+
+    Ok(Response::ok(RecoverResponse { tx_hash, audit }))
+}
+
+/// Lists the administrative actions taken on a deposit
+///
+/// # Path Parameters
+/// * `deposit_id` - The deposit ID
+#[utoipa::path(
+    get,
+    path = "/admin/deposits/{deposit_id}/audit",
+    tag = "admin",
+    params(("deposit_id" = String, Path, description = "The deposit ID")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Audit trail, newest first", body = Response<Vec<DepositAuditEntry>>),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorResponse),
+        (status = 404, description = "Deposit not found", body = ErrorResponse)
+    )
+)]
+pub async fn admin_deposit_audit_log(
+    State(state): State<Arc<HandlerState>>,
+    Operator(_operator): Operator,
+    Path(deposit_id): Path<String>,
+) -> ApiResult<Vec<DepositAuditEntry>> {
+    find_deposit(&state, &deposit_id).await?;
+    let entries = state
+        .orderbook
+        .get_deposit_audit_log(&deposit_id)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?;
+    Ok(Response::ok(entries))
+}
+
+async fn find_deposit(
+    state: &HandlerState,
+    deposit_id: &str,
+) -> Result<DepositResponse, Response<()>> {
+    state
+        .orderbook
+        .get_deposit(deposit_id)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?
+        .ok_or_else(deposit_not_found)
+}
+
+fn deposit_not_found() -> Response<()> {
+    Response::error(ApiErrorCode::DepositNotFound, "Deposit not found")
+}
+
+/// Checks that the operator gave a reason and returns it trimmed
+fn validate_reason(reason: &str) -> Result<&str, Response<()>> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(Response::error(
+            ApiErrorCode::InvalidRequest,
+            "A reason is required",
+        ));
+    }
+    if reason.len() > MAX_REASON_LENGTH {
+        return Err(Response::error(
+            ApiErrorCode::InvalidRequest,
+            format!("reason must be at most {} bytes", MAX_REASON_LENGTH),
+        ));
+    }
+    Ok(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_reason() {
+        assert_eq!(
+            validate_reason("  stuck in RPC outage ").unwrap(),
+            "stuck in RPC outage"
+        );
+        assert!(validate_reason("   ").is_err());
+        assert!(validate_reason(&"x".repeat(MAX_REASON_LENGTH + 1)).is_err());
+    }
+}
@@ -0,0 +1,614 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Json, async_trait,
+    body::Bytes,
+    extract::{FromRequest, FromRequestParts, OriginalUri, Request},
+    http::{HeaderMap, header, request::Parts},
+    response::{IntoResponse, Response as AxumResponse},
+};
+use moka::future::Cache;
+use serde::{Serialize, de::DeserializeOwned};
+use starknet::core::{
+    types::{BlockId, BlockTag, Felt, FunctionCall, TypedData},
+    utils::{get_selector_from_name, starknet_keccak},
+};
+use starknet::providers::{JsonRpcClient, Provider, ProviderError, jsonrpc::HttpTransport};
+use tracing::{Instrument, error};
+use utoipa::ToSchema;
+
+use crate::{
+    chain_id::ChainId,
+    metrics::{METRICS, UPSTREAM_STARKNET_RPC, upstream_span},
+    primitives::{ApiErrorCode, Response},
+    server::{handler::HandlerState, rate_limit::SignerRateLimit},
+};
+
+/// Header carrying the address of the signing Starknet account
+pub const ACCOUNT_HEADER: &str = "x-starknet-account";
+
+/// Header carrying the signature as comma separated felts
+pub const SIGNATURE_HEADER: &str = "x-starknet-signature";
+
+/// Header carrying a nonce previously issued by `GET /auth/nonce`
+pub const NONCE_HEADER: &str = "x-starknet-nonce";
+
+/// Header carrying the unix timestamp (seconds) after which the signature is rejected
+pub const EXPIRY_HEADER: &str = "x-starknet-expiry";
+
+/// Default lifetime of an issued nonce in seconds
+const DEFAULT_NONCE_TTL_SECS: u64 = 300;
+
+/// Maximum number of outstanding nonces kept in memory
+const NONCE_CACHE_SIZE: u64 = 100_000;
+
+/// SNIP-12 domain name used for OneSat request signatures
+const DOMAIN_NAME: &str = "OneSat";
+
+/// SNIP-12 domain version used for OneSat request signatures
+const DOMAIN_VERSION: &str = "1";
+
+/// SNIP-12 primary type of a signed OneSat request
+const PRIMARY_TYPE: &str = "OneSatRequest";
+
+/// Value returned by SNIP-6 accounts from `is_valid_signature` for a valid signature.
+/// This is the Cairo short string `'VALID'`.
+const VALID_SIGNATURE_MAGIC: Felt = Felt::from_hex_unchecked("0x56414c4944");
+
+/// A nonce issued to a client for signing a single request
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuthNonce {
+    /// The nonce as a hex felt
+    pub nonce: String,
+    /// Time after which the nonce can no longer be used
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Verifies SNIP-12 typed-data signatures on incoming requests
+///
+/// Clients fetch a nonce from `GET /auth/nonce`, sign an `OneSatRequest` typed-data
+/// message binding the HTTP method, path, body hash, nonce and expiry, and send the
+/// signature in the `X-Starknet-*` headers. The signature is checked by calling
+/// `is_valid_signature` on the signing account contract, and each nonce is accepted
+/// only once.
+pub struct RequestAuthenticator {
+    enabled: bool,
+    chain_id: Arc<ChainId>,
+    nonce_ttl_secs: u64,
+    nonces: Cache<Felt, ()>,
+    provider: JsonRpcClient<HttpTransport>,
+}
+
+impl RequestAuthenticator {
+    /// Creates a new RequestAuthenticator
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether signatures are required on protected routes
+    /// * `chain_id` - Chain ID used in the SNIP-12 domain, read when the first signature is checked
+    /// * `nonce_ttl_secs` - Optional nonce lifetime in seconds, defaults to 300
+    /// * `provider` - JSON-RPC client used to call `is_valid_signature`
+    pub fn new(
+        enabled: bool,
+        chain_id: Arc<ChainId>,
+        nonce_ttl_secs: Option<u64>,
+        provider: JsonRpcClient<HttpTransport>,
+    ) -> Self {
+        let nonce_ttl_secs = nonce_ttl_secs.unwrap_or(DEFAULT_NONCE_TTL_SECS);
+        let nonces = Cache::builder()
+            .time_to_live(Duration::from_secs(nonce_ttl_secs))
+            .max_capacity(NONCE_CACHE_SIZE)
+            .build();
+        Self {
+            enabled,
+            chain_id,
+            nonce_ttl_secs,
+            nonces,
+            provider,
+        }
+    }
+
+    /// Issues a fresh single-use nonce
+    pub async fn issue_nonce(&self) -> AuthNonce {
+        // 31 random bytes always fit in a felt
+        let nonce_bytes: [u8; 31] = rand::random();
+        let nonce = Felt::from_bytes_be_slice(&nonce_bytes);
+        self.nonces.insert(nonce, ()).await;
+        AuthNonce {
+            nonce: format!("{:#x}", nonce),
+            expires_at: chrono::Utc::now() + chrono::Duration::seconds(self.nonce_ttl_secs as i64),
+        }
+    }
+
+    /// Verifies the signature headers of a request
+    ///
+    /// # Returns
+    /// * `Ok(None)` when authentication is disabled
+    /// * `Ok(Some(account))` with the verified signer address
+    /// * `Err` with a 401 response if the signature is missing or invalid
+    pub async fn verify(
+        &self,
+        headers: &HeaderMap,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> Result<Option<Felt>, Response<()>> {
+        if !self.enabled {
+            return Ok(None);
+        }
+
+        self.verify_signature(headers, method, path, body)
+            .await
+            .map(Some)
+    }
+
+    /// Verifies the signature headers of a request, even when authentication is disabled
+    ///
+    /// # Returns
+    /// * `Ok(account)` with the verified signer address
+    /// * `Err` with a 401 response if the signature is missing or invalid
+    pub async fn verify_signature(
+        &self,
+        headers: &HeaderMap,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> Result<Felt, Response<()>> {
+        let account = parse_felt_header(headers, ACCOUNT_HEADER)?;
+        let nonce = parse_felt_header(headers, NONCE_HEADER)?;
+        let expiry = header_str(headers, EXPIRY_HEADER)?
+            .parse::<u64>()
+            .map_err(|_| unauthorized(format!("Invalid {} header", EXPIRY_HEADER)))?;
+        let signature = header_str(headers, SIGNATURE_HEADER)?
+            .split(',')
+            .map(|part| Felt::from_hex(part.trim()))
+            .collect::<Result<Vec<Felt>, _>>()
+            .map_err(|_| unauthorized(format!("Invalid {} header", SIGNATURE_HEADER)))?;
+
+        let now = chrono::Utc::now().timestamp() as u64;
+        if expiry < now {
+            return Err(unauthorized("Signature expired"));
+        }
+        if expiry > now + self.nonce_ttl_secs {
+            return Err(unauthorized("Signature expiry is too far in the future"));
+        }
+        if !self.nonces.contains_key(&nonce) {
+            return Err(unauthorized("Nonce is invalid or already used"));
+        }
+
+        let chain_id = self
+            .chain_id
+            .get()
+            .await
+            .map_err(|e| Response::internal_error(ApiErrorCode::RpcUnavailable, e))?;
+        let hash = Self::message_hash(chain_id, account, method, path, body, nonce, expiry)
+            .map_err(|e| {
+                error!(error = %e, "Failed to compute SNIP-12 message hash");
+                unauthorized("Failed to compute message hash")
+            })?;
+
+        if !self.is_valid_signature(account, hash, signature).await? {
+            return Err(unauthorized("Invalid signature"));
+        }
+
+        // Only the first request presenting the nonce may use it
+        if self.nonces.remove(&nonce).await.is_none() {
+            return Err(unauthorized("Nonce is invalid or already used"));
+        }
+
+        Ok(account)
+    }
+
+    /// Computes the SNIP-12 message hash of a request for the given account and chain
+    fn message_hash(
+        chain_id: Felt,
+        account: Felt,
+        method: &str,
+        path: &str,
+        body: &[u8],
+        nonce: Felt,
+        expiry: u64,
+    ) -> eyre::Result<Felt> {
+        let typed_data: TypedData = serde_json::from_value(serde_json::json!({
+            "types": {
+                "StarknetDomain": [
+                    { "name": "name", "type": "shortstring" },
+                    { "name": "version", "type": "shortstring" },
+                    { "name": "chainId", "type": "shortstring" },
+                    { "name": "revision", "type": "shortstring" }
+                ],
+                PRIMARY_TYPE: [
+                    { "name": "method", "type": "shortstring" },
+                    { "name": "path", "type": "string" },
+                    { "name": "body_hash", "type": "felt" },
+                    { "name": "nonce", "type": "felt" },
+                    { "name": "expiry", "type": "timestamp" }
+                ]
+            },
+            "primaryType": PRIMARY_TYPE,
+            "domain": {
+                "name": DOMAIN_NAME,
+                "version": DOMAIN_VERSION,
+                "chainId": format!("{:#x}", chain_id),
+                "revision": "1"
+            },
+            "message": {
+                "method": method,
+                "path": path,
+                "body_hash": format!("{:#x}", starknet_keccak(body)),
+                "nonce": format!("{:#x}", nonce),
+                "expiry": expiry
+            }
+        }))?;
+        Ok(typed_data.message_hash(account)?)
+    }
+
+    /// Calls `is_valid_signature` on the account contract
+    ///
+    /// Accounts that revert on an invalid signature are treated as returning false,
+    /// while transport failures surface as a 503.
+    async fn is_valid_signature(
+        &self,
+        account: Felt,
+        hash: Felt,
+        signature: Vec<Felt>,
+    ) -> Result<bool, Response<()>> {
+        let mut calldata = vec![hash, Felt::from(signature.len())];
+        calldata.extend(signature);
+
+        let call = FunctionCall {
+            contract_address: account,
+            entry_point_selector: get_selector_from_name("is_valid_signature")
+                .expect("Invalid selector"),
+            calldata,
+        };
+
+        let started = Instant::now();
+        let result = self
+            .provider
+            .call(call, BlockId::Tag(BlockTag::Latest))
+            .instrument(upstream_span(UPSTREAM_STARKNET_RPC, "is_valid_signature"))
+            .await;
+        let outcome = match &result {
+            Ok(_) => "ok",
+            Err(ProviderError::StarknetError(_)) => "reverted",
+            Err(_) => "error",
+        };
+        METRICS.observe_upstream(
+            UPSTREAM_STARKNET_RPC,
+            "is_valid_signature",
+            outcome,
+            started,
+        );
+
+        match result {
+            Ok(result) => Ok(matches!(
+                result.first(),
+                Some(value) if *value == VALID_SIGNATURE_MAGIC || *value == Felt::ONE
+            )),
+            Err(ProviderError::StarknetError(e)) => {
+                error!(account = %format!("{:#x}", account), error = %e, "is_valid_signature call failed");
+                Ok(false)
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to reach Starknet RPC for signature verification");
+                Err(Response::error(
+                    ApiErrorCode::RpcUnavailable,
+                    "Failed to verify signature",
+                ))
+            }
+        }
+    }
+}
+
+/// JSON request body accompanied by a verified SNIP-12 signature
+///
+/// When authentication is disabled in settings, `signer` is `None` and the body
+/// is accepted as-is. Handlers are responsible for checking that the signer is
+/// allowed to perform the requested mutation.
+pub struct SignedRequest<T> {
+    pub signer: Option<Felt>,
+    pub body: T,
+}
+
+#[async_trait]
+impl<T> FromRequest<Arc<HandlerState>> for SignedRequest<T>
+where
+    T: DeserializeOwned,
+{
+    type Rejection = AxumResponse;
+
+    async fn from_request(
+        req: Request,
+        state: &Arc<HandlerState>,
+    ) -> Result<Self, Self::Rejection> {
+        let request = RawRequest::read(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let signer = state
+            .authenticator
+            .verify(
+                &request.headers,
+                &request.method,
+                &request.path,
+                &request.body,
+            )
+            .await
+            .map_err(IntoResponse::into_response)?;
+        if let Some(signer) = &signer {
+            request.charge_signer(signer).await?;
+        }
+
+        Ok(Self {
+            signer,
+            body: request.json().map_err(IntoResponse::into_response)?,
+        })
+    }
+}
+
+/// JSON request body signed by the account owning the resource it mutates
+///
+/// Unlike [`SignedRequest`], the signature is required even when
+/// authentication is disabled in settings, for mutations that only the owner
+/// of a deposit may perform. Handlers must still check that `signer` is that
+/// owner.
+pub struct OwnerSignedRequest<T> {
+    pub signer: Felt,
+    pub body: T,
+}
+
+#[async_trait]
+impl<T> FromRequest<Arc<HandlerState>> for OwnerSignedRequest<T>
+where
+    T: DeserializeOwned,
+{
+    type Rejection = AxumResponse;
+
+    async fn from_request(
+        req: Request,
+        state: &Arc<HandlerState>,
+    ) -> Result<Self, Self::Rejection> {
+        let request = RawRequest::read(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let signer = state
+            .authenticator
+            .verify_signature(
+                &request.headers,
+                &request.method,
+                &request.path,
+                &request.body,
+            )
+            .await
+            .map_err(IntoResponse::into_response)?;
+        request.charge_signer(&signer).await?;
+
+        Ok(Self {
+            signer,
+            body: request.json().map_err(IntoResponse::into_response)?,
+        })
+    }
+}
+
+/// The parts of a request covered by its signature
+struct RawRequest {
+    method: String,
+    path: String,
+    headers: HeaderMap,
+    body: Bytes,
+    rate_limit: Option<SignerRateLimit>,
+}
+
+impl RawRequest {
+    async fn read(req: Request, state: &Arc<HandlerState>) -> Result<Self, Response<()>> {
+        let method = req.method().to_string();
+        // Sign the path as sent by the client, before any router nesting strips a prefix
+        let path = match req.extensions().get::<OriginalUri>() {
+            Some(OriginalUri(uri)) => uri.path().to_string(),
+            None => req.uri().path().to_string(),
+        };
+        let headers = req.headers().clone();
+        let rate_limit = req.extensions().get::<SignerRateLimit>().cloned();
+
+        let body = Bytes::from_request(req, state).await.map_err(|e| {
+            Response::error(ApiErrorCode::InvalidRequest, e.body_text()).with_status(e.status())
+        })?;
+
+        Ok(Self {
+            method,
+            path,
+            headers,
+            body,
+            rate_limit,
+        })
+    }
+
+    /// Charges the user budget of the route, if rate limited, to the verified signer
+    async fn charge_signer(&self, signer: &Felt) -> Result<(), AxumResponse> {
+        match &self.rate_limit {
+            Some(rate_limit) => rate_limit.check(signer).await,
+            None => Ok(()),
+        }
+    }
+
+    fn json<T: DeserializeOwned>(&self) -> Result<T, Response<()>> {
+        let Json(body) = Json::<T>::from_bytes(&self.body).map_err(|e| {
+            Response::error(ApiErrorCode::InvalidRequest, e.body_text()).with_status(e.status())
+        })?;
+        Ok(body)
+    }
+}
+
+/// Checks that the verified signer, if any, matches the expected account
+pub fn ensure_signer(signer: Option<Felt>, expected: &Felt) -> Result<(), Response<()>> {
+    match signer {
+        Some(signer) if signer != *expected => Err(Response::error(
+            ApiErrorCode::Forbidden,
+            "Signer is not authorized for this resource",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Partner authenticated by an `Authorization: Bearer <token>` header
+///
+/// Tokens are configured per partner in the `webhooks.partners` settings. When
+/// webhooks are not configured every request is rejected.
+pub struct Partner(pub String);
+
+#[async_trait]
+impl FromRequestParts<Arc<HandlerState>> for Partner {
+    type Rejection = Response<()>;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<HandlerState>,
+    ) -> Result<Self, Self::Rejection> {
+        let token = header_str(&parts.headers, header::AUTHORIZATION.as_str())?
+            .strip_prefix("Bearer ")
+            .ok_or_else(|| unauthorized("Expected a bearer token"))?;
+
+        state
+            .partner_tokens
+            .get(token)
+            .map(|partner| Self(partner.clone()))
+            .ok_or_else(|| unauthorized("Invalid bearer token"))
+    }
+}
+
+/// Operator authenticated by an `Authorization: Bearer <token>` header
+///
+/// Tokens are configured per operator in the `admin.operators` settings. When
+/// the admin API is not configured every request is rejected.
+pub struct Operator(pub String);
+
+#[async_trait]
+impl FromRequestParts<Arc<HandlerState>> for Operator {
+    type Rejection = Response<()>;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<HandlerState>,
+    ) -> Result<Self, Self::Rejection> {
+        let token = header_str(&parts.headers, header::AUTHORIZATION.as_str())?
+            .strip_prefix("Bearer ")
+            .ok_or_else(|| unauthorized("Expected a bearer token"))?;
+
+        state
+            .operator_tokens
+            .get(token)
+            .map(|operator| Self(operator.clone()))
+            .ok_or_else(|| unauthorized("Invalid bearer token"))
+    }
+}
+
+fn unauthorized<E: ToString>(error: E) -> Response<()> {
+    Response::error(ApiErrorCode::Unauthorized, error)
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Result<&'a str, Response<()>> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| unauthorized(format!("Missing {} header", name)))
+}
+
+fn parse_felt_header(headers: &HeaderMap, name: &str) -> Result<Felt, Response<()>> {
+    Felt::from_hex(header_str(headers, name)?)
+        .map_err(|_| unauthorized(format!("Invalid {} header", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+
+    use super::*;
+
+    /// Short string "SN_SEPOLIA"
+    const SEPOLIA_CHAIN_ID: &str = "0x534e5f5345504f4c4941";
+
+    fn create_authenticator(enabled: bool) -> RequestAuthenticator {
+        let provider = JsonRpcClient::new(HttpTransport::new(
+            Url::parse("http://localhost:5050").unwrap(),
+        ));
+        let chain_id = ChainId::new(
+            provider.clone(),
+            Some(Felt::from_hex_unchecked(SEPOLIA_CHAIN_ID)),
+        );
+        RequestAuthenticator::new(enabled, Arc::new(chain_id), None, provider)
+    }
+
+    #[test]
+    fn test_message_hash_binds_request() {
+        let chain_id = Felt::from_hex_unchecked(SEPOLIA_CHAIN_ID);
+        let account = Felt::from_hex_unchecked("0x123");
+        let nonce = Felt::from_hex_unchecked("0x456");
+        let path = format!("/deposit/{}/atomiq-swap-id", "ab".repeat(32));
+
+        let hash = RequestAuthenticator::message_hash(
+            chain_id,
+            account,
+            "POST",
+            &path,
+            b"{}",
+            nonce,
+            1_700_000_000,
+        )
+        .expect("Failed to compute message hash");
+        let same = RequestAuthenticator::message_hash(
+            chain_id,
+            account,
+            "POST",
+            &path,
+            b"{}",
+            nonce,
+            1_700_000_000,
+        )
+        .unwrap();
+        let other_body = RequestAuthenticator::message_hash(
+            chain_id,
+            account,
+            "POST",
+            &path,
+            b"{\"a\":1}",
+            nonce,
+            1_700_000_000,
+        )
+        .unwrap();
+        let other_account = RequestAuthenticator::message_hash(
+            chain_id,
+            Felt::from_hex_unchecked("0x789"),
+            "POST",
+            &path,
+            b"{}",
+            nonce,
+            1_700_000_000,
+        )
+        .unwrap();
+
+        assert_eq!(hash, same);
+        assert_ne!(hash, other_body);
+        assert_ne!(hash, other_account);
+    }
+
+    #[tokio::test]
+    async fn test_owner_signature_required_when_auth_disabled() {
+        let authenticator = create_authenticator(false);
+        let path = format!("/v1/deposit/{}/cancel", "ab".repeat(32));
+
+        let signer = authenticator
+            .verify(&HeaderMap::new(), "POST", &path, b"{}")
+            .await
+            .unwrap();
+        assert_eq!(signer, None);
+
+        let unsigned = authenticator
+            .verify_signature(&HeaderMap::new(), "POST", &path, b"{}")
+            .await
+            .unwrap_err();
+        assert_eq!(unsigned.code, Some(ApiErrorCode::Unauthorized));
+        assert_eq!(unsigned.status_code, axum::http::StatusCode::UNAUTHORIZED);
+    }
+}
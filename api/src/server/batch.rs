@@ -0,0 +1,422 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+
+use axum::{extract::State, http::HeaderMap};
+use sha2::{Digest, Sha256};
+use starknet::core::types::Felt;
+
+use crate::{
+    orderbook::{
+        batch::NewDeposit,
+        idempotency::{BatchOutcome, IdempotencyKey, IdempotencyKeyTaken},
+    },
+    primitives::{
+        ApiErrorCode, ApiResult, CreateDepositBatchRequest, CreateDepositRequest,
+        DepositBatchResponse, DepositResponse, ErrorResponse, MAX_BATCH_DEPOSITS, Response, Status,
+        VesuPool,
+    },
+    registry::AddressPrediction,
+    server::{
+        auth::SignedRequest,
+        handler::{
+            CheckedDeposit, HandlerState, check_deposit_request, ensure_vtoken_target,
+            parse_idempotency_key,
+        },
+        request_id::RequestId,
+        util::address_key,
+    },
+};
+
+/// Creates several deposits at once
+///
+/// Each deposit is validated like in `POST /deposit`. Deposits failing
+/// validation are reported in their result and do not prevent the others from
+/// being created. When the Vesu pools cannot be fetched, the deposits whose
+/// target still had to be checked against them are rejected with the Vesu error. The addresses of the valid deposits are predicted with one
+/// batched RPC request and the deposits are inserted in one transaction, so a
+/// failure of either fails the whole batch.
+///
+/// When authentication is enabled the request must be signed, and deposits of
+/// other users than the signer are rejected.
+///
+/// An `Idempotency-Key` header applies to the batch as a whole: replaying a key
+/// returns the results of its first use, including the rejected deposits, and
+/// reusing it with different deposits is rejected with 409.
+#[utoipa::path(
+    post,
+    path = "/deposits/batch",
+    tag = "deposits",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Client-generated key making retries return the original results")
+    ),
+    request_body = CreateDepositBatchRequest,
+    security((), ("snip12" = [])),
+    responses(
+        (status = 200, description = "Per-deposit results", body = Response<DepositBatchResponse>),
+        (status = 400, description = "Empty or oversized batch", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid signature", body = ErrorResponse),
+        (status = 409, description = "Idempotency key reused with a different batch", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+        (status = 503, description = "Starknet RPC unavailable", body = ErrorResponse)
+    )
+)]
+pub async fn create_deposit_batch(
+    State(state): State<Arc<HandlerState>>,
+    headers: HeaderMap,
+    request_id: RequestId,
+    SignedRequest {
+        signer,
+        body: request,
+    }: SignedRequest<CreateDepositBatchRequest>,
+) -> ApiResult<DepositBatchResponse> {
+    if request.deposits.is_empty() || request.deposits.len() > MAX_BATCH_DEPOSITS {
+        return Err(Response::error(
+            ApiErrorCode::InvalidRequest,
+            format!("A batch must contain 1 to {} deposits", MAX_BATCH_DEPOSITS),
+        ));
+    }
+
+    // Replayed before any upstream call, so that a retry succeeds even while Vesu is down
+    let idempotency_key = parse_idempotency_key(&headers)?.map(|key| IdempotencyKey {
+        scope: batch_scope(signer, &request.deposits),
+        key,
+        request_hash: batch_request_hash(&request.deposits),
+    });
+    if let Some(key) = &idempotency_key
+        && let Some(batch) = replay_idempotent_batch(&state, key).await?
+    {
+        return Ok(Response::ok(batch));
+    }
+
+    let checked = request
+        .deposits
+        .iter()
+        .map(|deposit| check_deposit_request(deposit, signer, &state.supported_assets))
+        .collect::<Vec<_>>();
+    // Vesu is only asked when some deposit has a target left to check
+    let pools = if checked.iter().any(Result::is_ok) {
+        state.vesu.pools(None).await.map_err(Response::from)
+    } else {
+        Ok(Arc::default())
+    };
+    let checked = check_targets(
+        &request.deposits,
+        checked,
+        pools.as_ref().map(|pools| pools.as_slice()),
+    );
+
+    // Deposits that passed validation with their new IDs, and the outcome of every deposit
+    let mut accepted = Vec::new();
+    let mut outcomes = Vec::with_capacity(checked.len());
+    for (deposit, checked) in request.deposits.iter().zip(&checked) {
+        let checked = match checked {
+            Ok(checked) => checked,
+            Err(e) => {
+                outcomes.push(BatchOutcome::Rejected {
+                    code: e.code.unwrap_or(ApiErrorCode::Internal),
+                    error: e.error.clone().unwrap_or_default(),
+                });
+                continue;
+            }
+        };
+        // Generate random 32-byte deposit ID and encode as hex
+        let deposit_id_bytes: [u8; 32] = rand::random();
+        let deposit_id = hex::encode(deposit_id_bytes);
+        let deposit_id_felt = Felt::from_hex(&deposit_id)
+            .map_err(|e| Response::internal_error(ApiErrorCode::Internal, e))?;
+        outcomes.push(BatchOutcome::Created {
+            deposit_id: deposit_id.clone(),
+        });
+        accepted.push((deposit, checked, deposit_id, deposit_id_felt));
+    }
+
+    let predictions = accepted
+        .iter()
+        .map(|(deposit, checked, _, deposit_id)| AddressPrediction {
+            user: checked.user_address,
+            deposit_id: *deposit_id,
+            action: deposit.action,
+            amount: deposit.amount.clone(),
+            token: checked.token,
+            target: checked.target_address,
+        })
+        .collect::<Vec<_>>();
+    let deposit_addresses = state
+        .vault_registry
+        .predict_addresses(&predictions)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::RpcUnavailable, e))?;
+
+    // Prices at creation time, kept so that exports can value the deposits later
+    let prices = state.coingecko.get_all_prices().await.unwrap_or_default();
+    let new_deposits = accepted
+        .into_iter()
+        .zip(deposit_addresses)
+        .map(
+            |((deposit, checked, deposit_id, _), deposit_address)| NewDeposit {
+                deposit_id,
+                user_address: &deposit.user_address,
+                action: deposit.action,
+                amount: &deposit.amount,
+                token: &deposit.token,
+                target_address: &deposit.target_address,
+                deposit_address: format!("{:#x}", deposit_address),
+                usd_price: prices.get(&checked.asset.coingecko_id).cloned(),
+                request_id: &request_id.0,
+            },
+        )
+        .collect::<Vec<_>>();
+
+    // Without the pools no deposit was created, so the key is left unused for
+    // a retry once Vesu is back rather than replaying the Vesu rejections
+    let created = state
+        .orderbook
+        .create_deposits(
+            &new_deposits,
+            idempotency_key
+                .as_ref()
+                .filter(|_| pools.is_ok())
+                .map(|key| (key, outcomes.as_slice())),
+        )
+        .await;
+
+    let mut created = match (created, &idempotency_key) {
+        (Ok(created), _) => created.into_iter(),
+        // A concurrent request with the same key won the race
+        (Err(e), Some(key)) if e.is::<IdempotencyKeyTaken>() => {
+            let batch = replay_idempotent_batch(&state, key).await?.ok_or_else(|| {
+                Response::error(
+                    ApiErrorCode::IdempotencyConflict,
+                    "Idempotency key is already in use",
+                )
+            })?;
+            return Ok(Response::ok(batch));
+        }
+        (Err(e), _) => {
+            return Err(Response::internal_error(ApiErrorCode::DbError, e));
+        }
+    };
+
+    let results = checked
+        .into_iter()
+        .map(|checked| match checked {
+            Ok(_) => Response::ok(created.next().expect("one deposit per checked request")),
+            Err(e) => rejection(e),
+        })
+        .collect();
+
+    Ok(Response::ok(batch_response(results)))
+}
+
+/// Checks the targets of the deposits that passed the other checks against the
+/// Vesu pools
+///
+/// If the pools could not be fetched, those deposits are rejected with the
+/// Vesu error, while the others keep their own rejection.
+fn check_targets<'a>(
+    deposits: &[CreateDepositRequest],
+    checked: Vec<Result<CheckedDeposit<'a>, Response<()>>>,
+    pools: Result<&[VesuPool], &Response<()>>,
+) -> Vec<Result<CheckedDeposit<'a>, Response<()>>> {
+    deposits
+        .iter()
+        .zip(checked)
+        .map(|(deposit, checked)| {
+            let checked = checked?;
+            ensure_vtoken_target(
+                checked.asset,
+                &deposit.target_address,
+                pools.map_err(Clone::clone)?,
+            )?;
+            Ok(checked)
+        })
+        .collect()
+}
+
+/// Counts the created and rejected deposits of a batch
+fn batch_response(results: Vec<Response<DepositResponse>>) -> DepositBatchResponse {
+    let rejected = results
+        .iter()
+        .filter(|result| result.status == Status::Error)
+        .count();
+
+    DepositBatchResponse {
+        created: results.len() - rejected,
+        rejected,
+        results,
+    }
+}
+
+/// Scopes the idempotency key of a batch to its signer and the users of its deposits
+fn batch_scope(signer: Option<Felt>, deposits: &[CreateDepositRequest]) -> String {
+    let users = deposits
+        .iter()
+        .map(|deposit| address_key(&deposit.user_address))
+        .collect::<BTreeSet<_>>();
+    let signer = signer.map(|signer| format!("{:#x}", signer));
+    let fingerprint = format!(
+        "{}|{}",
+        signer.unwrap_or_default(),
+        users.into_iter().collect::<Vec<_>>().join(",")
+    );
+    hex::encode(Sha256::digest(fingerprint.as_bytes()))
+}
+
+/// Hashes the normalised deposits of a batch, in request order
+fn batch_request_hash(deposits: &[CreateDepositRequest]) -> String {
+    let fingerprint = deposits
+        .iter()
+        .map(|deposit| {
+            format!(
+                "{}|{}|{}|{}|{}",
+                address_key(&deposit.user_address),
+                deposit.action,
+                deposit.amount.normalized(),
+                address_key(&deposit.token),
+                address_key(&deposit.target_address)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    hex::encode(Sha256::digest(fingerprint.as_bytes()))
+}
+
+/// Rebuilds the results of an earlier use of a batch idempotency key
+///
+/// # Returns
+/// * `Ok(None)` if the key was not used before
+/// * `Ok(Some(batch))` if the key was used with the same batch
+/// * 409 if the key was used with a different batch
+async fn replay_idempotent_batch(
+    state: &HandlerState,
+    key: &IdempotencyKey,
+) -> Result<Option<DepositBatchResponse>, Response<()>> {
+    let database_error = |e: eyre::Report| Response::internal_error(ApiErrorCode::DbError, e);
+
+    let Some(record) = state
+        .orderbook
+        .get_batch_idempotency_record(key)
+        .await
+        .map_err(database_error)?
+    else {
+        return Ok(None);
+    };
+
+    if record.request_hash != key.request_hash {
+        return Err(Response::error(
+            ApiErrorCode::IdempotencyConflict,
+            "Idempotency key was already used with a different batch",
+        ));
+    }
+
+    let deposit_ids = record
+        .outcomes
+        .iter()
+        .filter_map(|outcome| match outcome {
+            BatchOutcome::Created { deposit_id } => Some(deposit_id.clone()),
+            BatchOutcome::Rejected { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    let mut deposits: HashMap<String, DepositResponse> = state
+        .orderbook
+        .get_deposits_by_ids(&deposit_ids)
+        .await
+        .map_err(database_error)?
+        .into_iter()
+        .map(|deposit| (deposit.deposit_id.clone(), deposit))
+        .collect();
+
+    let results = record
+        .outcomes
+        .into_iter()
+        .map(|outcome| match outcome {
+            BatchOutcome::Created { deposit_id } => deposits
+                .remove(&deposit_id)
+                .map(Response::ok)
+                .ok_or_else(|| {
+                    Response::internal_error(
+                        ApiErrorCode::DbError,
+                        format!("Deposit {} of an idempotent batch not found", deposit_id),
+                    )
+                }),
+            BatchOutcome::Rejected { code, error } => Ok(rejection(Response::error(code, error))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Some(batch_response(results)))
+}
+
+/// Turns the error that rejected a deposit into its batch result
+fn rejection(error: Response<()>) -> Response<DepositResponse> {
+    Response {
+        status: error.status,
+        result: None,
+        error: error.error,
+        code: error.code,
+        status_code: error.status_code,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{decimal, wbtc};
+
+    fn deposit(user_address: &str, amount: &str) -> CreateDepositRequest {
+        CreateDepositRequest {
+            user_address: user_address.to_string(),
+            action: 1,
+            amount: decimal(amount),
+            token: "0x0A".to_string(),
+            target_address: "0xb".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_vesu_failure_only_rejects_deposits_to_check() {
+        let assets = vec![wbtc()];
+        let deposits = [
+            deposit("0x123", "100"),
+            CreateDepositRequest {
+                action: 99,
+                ..deposit("0x123", "100")
+            },
+        ];
+        let checked = deposits
+            .iter()
+            .map(|deposit| check_deposit_request(deposit, None, &assets))
+            .collect();
+        let vesu_down = Response::error(ApiErrorCode::UpstreamVesuFailed, "Vesu request failed");
+
+        let checked = check_targets(&deposits, checked, Err(&vesu_down));
+        let codes = checked
+            .iter()
+            .map(|checked| checked.as_ref().err().and_then(|e| e.code))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            [
+                Some(ApiErrorCode::UpstreamVesuFailed),
+                Some(ApiErrorCode::UnknownAction)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_batch_idempotency_key_is_normalised() {
+        let batch = [deposit("0x123", "100"), deposit("0xabc", "200")];
+        let respelled = [deposit("0x0123", "100.0"), deposit("0xABC", "200")];
+        let reordered = [deposit("0xabc", "200"), deposit("0x123", "100")];
+
+        assert_eq!(batch_scope(None, &batch), batch_scope(None, &respelled));
+        assert_eq!(batch_scope(None, &batch), batch_scope(None, &reordered));
+        assert_ne!(
+            batch_scope(None, &batch),
+            batch_scope(Some(Felt::from_hex_unchecked("0x123")), &batch)
+        );
+        assert_eq!(batch_request_hash(&batch), batch_request_hash(&respelled));
+        assert_ne!(batch_request_hash(&batch), batch_request_hash(&reordered));
+    }
+}
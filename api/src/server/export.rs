@@ -0,0 +1,296 @@
+use std::sync::Arc;
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response as AxumResponse},
+};
+use futures_util::{TryStreamExt, stream};
+use starknet::core::types::Felt;
+use tracing::error;
+
+use crate::{
+    orderbook::export::ExportedDeposit,
+    primitives::{
+        ApiErrorCode, Asset, DepositCursor, DepositExportQuery, DepositExportRow, ErrorResponse,
+        ExportFormat, Response,
+    },
+    server::{
+        handler::HandlerState,
+        util::{from_base_units, same_address},
+    },
+};
+
+/// Number of deposits fetched from the orderbook per chunk of the export
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+/// Columns of the CSV export, in order
+const CSV_HEADER: &str = "created_at,deposit_id,status,symbol,token,amount,amount_base_units,\
+                          usd_value,action,target_address,deposit_address,deposit_tx_hash,\
+                          atomiq_swap_id\n";
+
+/// Exports a user's deposit history as CSV or JSON
+///
+/// Deposits are streamed oldest first. Amounts are converted to token units
+/// using the decimals of the supported assets, and valued at the token price
+/// recorded when each deposit was created.
+///
+/// # Path Parameters
+/// * `user_address` - The user's wallet address
+///
+/// # Query Parameters
+/// * `format` - `csv` (default) or `json`
+/// * `from` - Only export deposits created at or after this time
+/// * `to` - Only export deposits created strictly before this time
+#[utoipa::path(
+    get,
+    path = "/deposits/user/{user_address}/export",
+    tag = "deposits",
+    params(
+        ("user_address" = String, Path, description = "The user's wallet address"),
+        DepositExportQuery
+    ),
+    responses(
+        (status = 200, description = "Deposit history", content(
+            (String = "text/csv"),
+            (Vec<DepositExportRow> = "application/json")
+        )),
+        (status = 400, description = "Invalid address or time range", body = ErrorResponse)
+    )
+)]
+pub async fn export_user_deposits(
+    State(state): State<Arc<HandlerState>>,
+    Path(user_address): Path<String>,
+    Query(query): Query<DepositExportQuery>,
+) -> Result<AxumResponse, Response<()>> {
+    Felt::from_hex(&user_address).map_err(|e| {
+        Response::error(
+            ApiErrorCode::InvalidAddress,
+            format!("Invalid user_address: {}", e),
+        )
+    })?;
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from >= to
+    {
+        return Err(Response::error(
+            ApiErrorCode::InvalidRequest,
+            "from must be before to",
+        ));
+    }
+
+    let format = query.format.unwrap_or_default();
+    let (content_type, extension) = match format {
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "csv"),
+        ExportFormat::Json => ("application/json", "json"),
+    };
+    let disposition = format!(
+        "attachment; filename=\"deposits-{}.{}\"",
+        user_address, extension
+    );
+
+    let export = Export {
+        state,
+        user_address,
+        from: query.from,
+        to: query.to,
+        format,
+        cursor: None,
+        started: false,
+        finished: false,
+    };
+    let body = Body::from_stream(
+        stream::try_unfold(export, Export::next_chunk)
+            .inspect_err(|e| error!("Failed to export deposits: {}", e)),
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Position of an export stream in the user's deposits
+struct Export {
+    state: Arc<HandlerState>,
+    user_address: String,
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+    format: ExportFormat,
+    cursor: Option<DepositCursor>,
+    started: bool,
+    finished: bool,
+}
+
+impl Export {
+    /// Fetches the next batch of deposits and renders it, or ends the stream
+    async fn next_chunk(mut self) -> eyre::Result<Option<(Bytes, Self)>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let deposits = self
+            .state
+            .orderbook
+            .get_deposits_for_export(
+                &self.user_address,
+                self.from,
+                self.to,
+                self.cursor.as_ref(),
+                EXPORT_BATCH_SIZE,
+            )
+            .await?;
+        self.finished = (deposits.len() as i64) < EXPORT_BATCH_SIZE;
+        self.cursor = deposits.last().map(|exported| DepositCursor {
+            created_at: exported.deposit.created_at,
+            deposit_id: exported.deposit.deposit_id.clone(),
+        });
+
+        let mut chunk = String::new();
+        if !self.started {
+            chunk.push_str(match self.format {
+                ExportFormat::Csv => CSV_HEADER,
+                ExportFormat::Json => "[",
+            });
+        }
+        for exported in deposits {
+            let row = export_row(exported, &self.state.supported_assets);
+            match self.format {
+                ExportFormat::Csv => chunk.push_str(&csv_line(&row)),
+                ExportFormat::Json => {
+                    if self.started {
+                        chunk.push(',');
+                    }
+                    chunk.push_str(&serde_json::to_string(&row)?);
+                }
+            }
+            self.started = true;
+        }
+        self.started = true;
+        if self.finished && self.format == ExportFormat::Json {
+            chunk.push(']');
+        }
+
+        Ok(Some((Bytes::from(chunk), self)))
+    }
+}
+
+/// Converts a deposit into an export row, valued at its price at creation time
+fn export_row(exported: ExportedDeposit, assets: &[Asset]) -> DepositExportRow {
+    let deposit = exported.deposit;
+    let asset = assets
+        .iter()
+        .find(|asset| same_address(&asset.address, &deposit.token));
+    let amount = asset.map(|asset| from_base_units(&deposit.amount, asset.decimals.into()));
+    let usd_value = amount
+        .as_ref()
+        .zip(exported.usd_price.as_ref())
+        .map(|(amount, price)| (amount * price).normalized());
+
+    DepositExportRow {
+        created_at: deposit.created_at,
+        deposit_id: deposit.deposit_id,
+        status: deposit.status,
+        symbol: asset.map(|asset| asset.symbol.clone()),
+        token: deposit.token,
+        amount,
+        amount_base_units: deposit.amount,
+        usd_value,
+        action: deposit.action,
+        target_address: deposit.target_address,
+        deposit_address: deposit.deposit_address,
+        deposit_tx_hash: deposit.deposit_tx_hash,
+        atomiq_swap_id: deposit.atomiq_swap_id,
+    }
+}
+
+/// Renders a row as a CSV line, in the order of [`CSV_HEADER`]
+fn csv_line(row: &DepositExportRow) -> String {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    let fields = [
+        row.created_at.to_rfc3339(),
+        row.deposit_id.clone(),
+        row.status.to_string(),
+        optional(row.symbol.clone()),
+        row.token.clone(),
+        optional(row.amount.as_ref().map(ToString::to_string)),
+        row.amount_base_units.to_string(),
+        optional(row.usd_value.as_ref().map(ToString::to_string)),
+        row.action.to_string(),
+        row.target_address.clone(),
+        row.deposit_address.clone(),
+        optional(row.deposit_tx_hash.clone()),
+        optional(row.atomiq_swap_id.clone()),
+    ];
+    let mut line = fields
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break
+///
+/// Fields starting like a spreadsheet formula are prefixed with `'` and quoted,
+/// so that user-supplied values such as `atomiq_swap_id` are shown as text
+/// rather than evaluated when the export is opened.
+fn csv_field(field: &str) -> String {
+    if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("\"'{}\"", field.replace('"', "\"\""))
+    } else if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::{decimal, deposit, wbtc},
+        primitives::{DepositResponse, DepositStatus},
+    };
+
+    #[test]
+    fn test_export_row_uses_asset_decimals_and_creation_price() {
+        let assets = vec![wbtc()];
+        let exported = ExportedDeposit {
+            deposit: DepositResponse {
+                token: "0xA".to_string(),
+                status: DepositStatus::Deposited,
+                deposit_tx_hash: Some("0xf".to_string()),
+                atomiq_swap_id: Some("swap, \"1\"".to_string()),
+                ..deposit()
+            },
+            usd_price: Some(decimal("60000")),
+        };
+
+        let row = export_row(exported, &assets);
+        assert_eq!(row.symbol.as_deref(), Some("WBTC"));
+        assert_eq!(row.amount.as_ref().unwrap().to_string(), "0.25");
+        assert_eq!(row.usd_value.as_ref().unwrap().to_string(), "15000");
+        assert_eq!(
+            csv_line(&row),
+            "2025-10-09T08:53:20+00:00,d1,deposited,WBTC,0xA,0.25,25000000,15000,0,0xb,0xd,0xf,\
+             \"swap, \"\"1\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_field_neutralises_formulas() {
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("+1"), "\"'+1\"");
+        assert_eq!(csv_field("-1"), "\"'-1\"");
+        assert_eq!(csv_field("@SUM(A1)"), "\"'@SUM(A1)\"");
+        assert_eq!(csv_field("\tcmd"), "\"'\tcmd\"");
+        assert_eq!(csv_field("\rcmd"), "\"'\rcmd\"");
+        assert_eq!(csv_field("swap-1"), "swap-1");
+    }
+}
@@ -1,41 +1,244 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use axum::extract::{Json, Path, Query, State};
-use bigdecimal::BigDecimal;
-use reqwest::StatusCode;
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue},
+    response::{
+        IntoResponse, Response as AxumResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use bigdecimal::{BigDecimal, Zero, num_bigint::BigInt};
+use futures_util::{Stream, stream};
 use serde::Deserialize;
-use serde_json::Value;
-use starknet::core::types::Felt;
+use sha2::{Digest, Sha256};
+use starknet::{
+    core::types::Felt,
+    providers::{JsonRpcClient, Provider, ProviderError, jsonrpc::HttpTransport},
+};
+use tokio::sync::{broadcast, watch};
+use tracing::{error, info};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
+    chain_id::ChainId,
     coingecko::CoingeckoFiatProvider,
-    orderbook::OrderbookProvider,
-    primitives::{ApiResult, Asset, CreateDepositRequest, DepositResponse, Response},
+    erc20,
+    fees::DeployFeeEstimator,
+    orderbook::{
+        OrderbookProvider,
+        changes::{DepositChange, DepositChangeFeed},
+        idempotency::{IdempotencyKey, IdempotencyKeyTaken},
+    },
+    primitives::{
+        ApiErrorCode, ApiResult, Asset, CancelDepositRequest, CreateDepositRequest,
+        CreateWebhookRequest, CreatedWebhookSubscription, DEFAULT_PAGE_LIMIT, DependencyCheck,
+        DepositCursor, DepositFilter, DepositListQuery, DepositPage, DepositResponse,
+        DepositStatus, ErrorResponse, KNOWN_ACTIONS, MAX_PAGE_LIMIT, PriceFreshness,
+        ReadinessReport, Response, Status, VesuHistoryEntry, VesuPool, VesuPosition,
+        WEBHOOK_EVENTS, WebhookDelivery, WebhookDeliveryQuery, WebhookSubscription,
+    },
+    recovery::RecoveryAccount,
     registry::VaultRegistry,
+    server::{
+        auth::{
+            AuthNonce, OwnerSignedRequest, Partner, RequestAuthenticator, SignedRequest,
+            ensure_signer,
+        },
+        request_id::RequestId,
+        util::{canonical_address, same_address},
+        versioning::{LegacyAlias, NEXT_CURSOR_HEADER},
+    },
+    shutdown,
+    stats::DepositStatsProvider,
+    vesu::VesuClient,
+    webhooks,
 };
 
 pub struct HandlerState {
     pub coingecko: Arc<CoingeckoFiatProvider>,
+    pub deploy_fees: Arc<DeployFeeEstimator>,
+    pub stats: Arc<DepositStatsProvider>,
     pub supported_assets: Vec<Asset>,
-    pub vesu_api_base_url: String,
+    pub vesu: Arc<VesuClient>,
     pub vault_registry: Arc<VaultRegistry>,
     pub orderbook: Arc<OrderbookProvider>,
+    pub authenticator: Arc<RequestAuthenticator>,
+    pub deposit_changes: Arc<DepositChangeFeed>,
+    /// Partner names keyed by their webhook management bearer token
+    pub partner_tokens: HashMap<String, String>,
+    /// Operator names keyed by their admin API bearer token
+    pub operator_tokens: HashMap<String, String>,
+    /// Account sending `recover()` to deposit addresses, if configured
+    pub recovery: Option<Arc<RecoveryAccount>>,
+    pub provider: JsonRpcClient<HttpTransport>,
+    /// Chain ID the RPC must report for the service to be ready
+    pub chain_id: Arc<ChainId>,
+    /// Maximum age of a cached price before the asset is reported stale
+    pub max_price_age_secs: u64,
+    /// Set to true once shutdown is requested, ending long-lived streams
+    pub shutdown: watch::Receiver<bool>,
 }
 
+/// Time allowed for each readiness check
+const READINESS_CHECK_TIMEOUT_SECS: u64 = 3;
+
+/// Header carrying a client-generated key that makes `POST /deposit` retries safe
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Maximum length of an idempotency key
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
 /// Health check endpoint that returns the service status
 ///
 /// # Returns
 /// * A static string indicating the service is online
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "Service is online", body = String, content_type = "text/plain"))
+)]
 pub async fn get_health() -> &'static str {
     "Online"
 }
 
+/// Readiness check of the service dependencies
+///
+/// Checks the database pool, calls `chain_id` on the Starknet RPC and compares it
+/// to the expected network, and reports how fresh the cached price of every
+/// supported asset is. All checks are critical.
+///
+/// # Returns
+/// * 200 with the breakdown when every check passes
+/// * 503 with the breakdown when any check fails
+#[utoipa::path(
+    get,
+    path = "/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "All dependencies are healthy", body = Response<ReadinessReport>),
+        (status = 503, description = "A dependency is failing", body = Response<ReadinessReport>)
+    )
+)]
+pub async fn get_ready(State(state): State<Arc<HandlerState>>) -> Response<ReadinessReport> {
+    let (database, starknet_rpc) = tokio::join!(
+        check_dependency(async {
+            state.orderbook.ping().await.map_err(|e| {
+                error!("Readiness database check failed: {}", e);
+                ApiErrorCode::DbError.message().to_string()
+            })
+        }),
+        check_dependency(async {
+            let rpc_unavailable = |e: ProviderError| {
+                error!("Readiness chain_id check failed: {}", e);
+                ApiErrorCode::RpcUnavailable.message().to_string()
+            };
+            let expected_chain_id = state.chain_id.get().await.map_err(rpc_unavailable)?;
+            let chain_id = state.provider.chain_id().await.map_err(rpc_unavailable)?;
+            if chain_id != expected_chain_id {
+                return Err(format!(
+                    "Chain ID mismatch: expected {:#x}, got {:#x}",
+                    expected_chain_id, chain_id
+                ));
+            }
+            Ok(())
+        }),
+    );
+
+    let prices = state.coingecko.get_all_prices().await.unwrap_or_default();
+    let updated_at = state.coingecko.price_updated_at();
+    let now = chrono::Utc::now();
+    let prices = state
+        .supported_assets
+        .iter()
+        .map(|asset| {
+            let age_secs = updated_at
+                .get(&asset.coingecko_id)
+                .map(|updated_at| (now - *updated_at).num_seconds());
+            let fresh = prices.contains_key(&asset.coingecko_id)
+                && age_secs.is_some_and(|age| age <= state.max_price_age_secs as i64);
+            PriceFreshness {
+                symbol: asset.symbol.clone(),
+                fresh,
+                age_secs,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let ready = database.ok && starknet_rpc.ok && prices.iter().all(|price| price.fresh);
+    let report = ReadinessReport {
+        ready,
+        database,
+        starknet_rpc,
+        prices,
+    };
+
+    if ready {
+        Response::ok(report)
+    } else {
+        Response {
+            status: Status::Error,
+            result: Some(report),
+            error: Some(ApiErrorCode::NotReady.message().to_string()),
+            code: Some(ApiErrorCode::NotReady),
+            status_code: ApiErrorCode::NotReady.status_code(),
+        }
+    }
+}
+
+/// Runs a readiness check with a timeout, measuring its latency
+async fn check_dependency<F>(check: F) -> DependencyCheck
+where
+    F: Future<Output = Result<(), String>>,
+{
+    let started = Instant::now();
+    let result = tokio::time::timeout(Duration::from_secs(READINESS_CHECK_TIMEOUT_SECS), check)
+        .await
+        .unwrap_or_else(|_| Err("Timed out".to_string()));
+    DependencyCheck {
+        ok: result.is_ok(),
+        latency_ms: started.elapsed().as_millis() as u64,
+        error: result.err(),
+    }
+}
+
+/// Issues a single-use nonce for signing a deposit mutation
+///
+/// # Returns
+/// The nonce and the time after which it expires
+#[utoipa::path(
+    get,
+    path = "/auth/nonce",
+    tag = "auth",
+    responses((status = 200, description = "Issued nonce", body = Response<AuthNonce>))
+)]
+pub async fn get_auth_nonce(State(state): State<Arc<HandlerState>>) -> ApiResult<AuthNonce> {
+    Ok(Response::ok(state.authenticator.issue_nonce().await))
+}
+
+/// Lists the supported assets with their latest cached USD prices
+#[utoipa::path(
+    get,
+    path = "/assets",
+    tag = "assets",
+    responses(
+        (status = 200, description = "Supported assets", body = Response<Vec<Asset>>),
+        (status = 500, description = "Failed to read prices", body = ErrorResponse)
+    )
+)]
 pub async fn supported_assets(State(state): State<Arc<HandlerState>>) -> ApiResult<Vec<Asset>> {
     let prices = state
         .coingecko
         .get_all_prices()
         .await
-        .map_err(|e| Response::error(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
+        .map_err(|e| Response::internal_error(ApiErrorCode::UpstreamPriceFailed, e))?;
     let supported_assets = state
         .supported_assets
         .iter()
@@ -50,6 +253,7 @@ pub async fn supported_assets(State(state): State<Arc<HandlerState>>) -> ApiResu
                 decimals: asset.decimals,
                 coingecko_id: asset.coingecko_id.clone(),
                 address: asset.address.clone(),
+                min_amount: asset.min_amount.clone(),
                 price: price,
             }
         })
@@ -57,143 +261,157 @@ pub async fn supported_assets(State(state): State<Arc<HandlerState>>) -> ApiResu
     Ok(Response::ok(supported_assets))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct VesuPositionsQuery {
+    /// The user's wallet address
     #[serde(rename = "walletAddress")]
     pub wallet_address: String,
 }
 
-#[derive(Deserialize)]
-pub struct VesuPositionsResponse {
-    pub data: Value,
-}
-
+/// Returns the user's positions from the Vesu API
+#[utoipa::path(
+    get,
+    path = "/vesu/positions",
+    tag = "vesu",
+    params(VesuPositionsQuery),
+    responses(
+        (status = 200, description = "Vesu positions", body = Response<Vec<VesuPosition>>),
+        (status = 400, description = "Vesu rejected the request", body = ErrorResponse),
+        (status = 404, description = "Not found on Vesu", body = ErrorResponse),
+        (status = 502, description = "Vesu request failed", body = ErrorResponse),
+        (status = 504, description = "Vesu request timed out", body = ErrorResponse)
+    )
+)]
 pub async fn vesu_positions(
     Query(query): Query<VesuPositionsQuery>,
     State(state): State<Arc<HandlerState>>,
-) -> ApiResult<Value> {
-    let url = format!("{}/positions", state.vesu_api_base_url);
-    let client = reqwest::Client::new();
-    let response = client
-        .get(url)
-        .query(&[("walletAddress", query.wallet_address)])
-        .send()
-        .await
-        .map_err(|e| Response::error(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
-    let body = response
-        .text()
-        .await
-        .map_err(|e| Response::error(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
-    let data: VesuPositionsResponse = serde_json::from_str(&body)
-        .map_err(|e| Response::error(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
-    Ok(Response::ok(data.data))
+) -> ApiResult<Vec<VesuPosition>> {
+    let data = state.vesu.positions(&query.wallet_address).await?;
+    Ok(Response::ok(data))
 }
 
+/// Returns the user's transaction history from the Vesu API
+#[utoipa::path(
+    get,
+    path = "/vesu/history",
+    tag = "vesu",
+    params(VesuPositionsQuery),
+    responses(
+        (status = 200, description = "Vesu history", body = Response<Vec<VesuHistoryEntry>>),
+        (status = 400, description = "Vesu rejected the request", body = ErrorResponse),
+        (status = 404, description = "Not found on Vesu", body = ErrorResponse),
+        (status = 502, description = "Vesu request failed", body = ErrorResponse),
+        (status = 504, description = "Vesu request timed out", body = ErrorResponse)
+    )
+)]
 pub async fn vesu_history(
     Query(query): Query<VesuPositionsQuery>,
     State(state): State<Arc<HandlerState>>,
-) -> ApiResult<Value> {
-    let url = format!(
-        "{}/users/{}/history",
-        state.vesu_api_base_url, query.wallet_address
-    );
-    let client = reqwest::Client::new();
-    let response = client
-        .get(url)
-        .send()
-        .await
-        .map_err(|e| Response::error(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
-    let body = response
-        .text()
-        .await
-        .map_err(|e| Response::error(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
-    let data: VesuPositionsResponse = serde_json::from_str(&body)
-        .map_err(|e| Response::error(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
-    Ok(Response::ok(data.data))
+) -> ApiResult<Vec<VesuHistoryEntry>> {
+    let data = state.vesu.history(&query.wallet_address).await?;
+    Ok(Response::ok(data))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct VesuPoolsQuery {
+    /// Only return this pool
     #[serde(rename = "poolAddress")]
     pub pool_address: Option<String>,
 }
 
+/// Returns all pools, or a single pool, from the Vesu API
+#[utoipa::path(
+    get,
+    path = "/vesu/pools",
+    tag = "vesu",
+    params(VesuPoolsQuery),
+    responses(
+        (status = 200, description = "Vesu pools", body = Response<Vec<VesuPool>>),
+        (status = 400, description = "Vesu rejected the request", body = ErrorResponse),
+        (status = 404, description = "Not found on Vesu", body = ErrorResponse),
+        (status = 502, description = "Vesu request failed", body = ErrorResponse),
+        (status = 504, description = "Vesu request timed out", body = ErrorResponse)
+    )
+)]
 pub async fn vesu_pools(
     Query(query): Query<VesuPoolsQuery>,
     State(state): State<Arc<HandlerState>>,
-) -> ApiResult<Value> {
-    let url = match query.pool_address {
-        Some(pool_address) => format!("{}/pools/{}", state.vesu_api_base_url, pool_address),
-        None => format!("{}/pools", state.vesu_api_base_url),
-    };
-    let client = reqwest::Client::new();
-    let response = client
-        .get(url)
-        .send()
-        .await
-        .map_err(|e| Response::error(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
-    let body = response
-        .text()
-        .await
-        .map_err(|e| Response::error(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
-    let data: VesuPositionsResponse = serde_json::from_str(&body)
-        .map_err(|e| Response::error(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
-    Ok(Response::ok(data.data))
+) -> ApiResult<Vec<VesuPool>> {
+    let pools = state.vesu.pools(query.pool_address.as_deref()).await?;
+    Ok(Response::ok(pools.as_ref().clone()))
 }
 
 /// Creates a new deposit and returns the deposit details
 ///
 /// This endpoint:
-/// 1. Validates the request parameters
+/// 1. Validates the request parameters against the supported assets and Vesu pools
 /// 2. Calls the registry contract to predict the deposit address
 /// 3. Generates a unique 32-byte deposit ID
 /// 4. Stores the deposit in the database with status "created"
 /// 5. Returns all deposit information
+///
+/// When authentication is enabled the request must be signed by `user_address`.
+///
+/// Requests carrying an `Idempotency-Key` header are safe to retry: replaying a key
+/// returns the deposit created by its first use, and reusing a key with a different
+/// request body is rejected with 409.
+#[utoipa::path(
+    post,
+    path = "/deposit",
+    tag = "deposits",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Client-generated key making retries return the original deposit")
+    ),
+    request_body = CreateDepositRequest,
+    security((), ("snip12" = [])),
+    responses(
+        (status = 200, description = "Created deposit", body = Response<DepositResponse>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid signature", body = ErrorResponse),
+        (status = 403, description = "Signer is not the user", body = ErrorResponse),
+        (status = 409, description = "Idempotency key reused with a different request", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
+    )
+)]
 pub async fn create_deposit(
     State(state): State<Arc<HandlerState>>,
-    Json(request): Json<CreateDepositRequest>,
+    headers: HeaderMap,
+    request_id: RequestId,
+    SignedRequest {
+        signer,
+        body: request,
+    }: SignedRequest<CreateDepositRequest>,
 ) -> ApiResult<DepositResponse> {
-    // Validate and parse Felt addresses
-    let user_address = Felt::from_hex(&request.user_address).map_err(|e| {
-        Response::error(
-            format!("Invalid user_address: {}", e),
-            StatusCode::BAD_REQUEST,
-        )
-    })?;
+    let CheckedDeposit {
+        user_address,
+        token,
+        target_address,
+        asset,
+    } = check_deposit_request(&request, signer, &state.supported_assets)?;
 
-    let token = Felt::from_hex(&request.token).map_err(|e| {
-        Response::error(
-            format!("Invalid token address: {}", e),
-            StatusCode::BAD_REQUEST,
-        )
-    })?;
-
-    let target_address = Felt::from_hex(&request.target_address).map_err(|e| {
-        Response::error(
-            format!("Invalid target_address: {}", e),
-            StatusCode::BAD_REQUEST,
-        )
-    })?;
-
-    // Validate amount is positive
-    let zero = BigDecimal::from_str("0").unwrap();
-    if request.amount <= zero {
-        return Err(Response::error(
-            "Amount must be positive",
-            StatusCode::BAD_REQUEST,
-        ));
+    // Replayed before any upstream call, so that a retry succeeds even while Vesu is down
+    let idempotency_key = parse_idempotency_key(&headers)?.map(|key| IdempotencyKey {
+        scope: format!("{:#x}", user_address),
+        key,
+        request_hash: deposit_request_hash(&user_address, &request, &token, &target_address),
+    });
+    if let Some(key) = &idempotency_key
+        && let Some(deposit) = replay_idempotent_deposit(&state, key).await?
+    {
+        return Ok(Response::ok(deposit));
     }
 
+    let pools = state.vesu.pools(None).await?;
+    ensure_vtoken_target(asset, &request.target_address, &pools)?;
+
     // Generate random 32-byte deposit ID and encode as hex
     let deposit_id_bytes: [u8; 32] = rand::random();
     let deposit_id = hex::encode(deposit_id_bytes);
 
-    let deposit_id_felt = Felt::from_hex(&deposit_id).map_err(|e| {
-        Response::error(
-            format!("Invalid deposit_id: {}", e),
-            StatusCode::BAD_REQUEST,
-        )
-    })?;
+    let deposit_id_felt = Felt::from_hex(&deposit_id)
+        .map_err(|e| Response::internal_error(ApiErrorCode::Internal, e))?;
 
     // Call registry contract to predict deposit address
     let deposit_address_felt = state
@@ -207,15 +425,18 @@ pub async fn create_deposit(
             &target_address,
         )
         .await
-        .map_err(|e| {
-            Response::error(
-                format!("Failed to predict deposit address: {}", e),
-                StatusCode::INTERNAL_SERVER_ERROR,
-            )
-        })?;
+        .map_err(|e| Response::internal_error(ApiErrorCode::RpcUnavailable, e))?;
 
     let deposit_address = format!("{:#x}", deposit_address_felt);
 
+    // Price at creation time, kept so that exports can value the deposit later
+    let usd_price = state
+        .coingecko
+        .get_all_prices()
+        .await
+        .ok()
+        .and_then(|mut prices| prices.remove(&asset.coingecko_id));
+
     // Insert into database
     let deposit = state
         .orderbook
@@ -229,16 +450,249 @@ pub async fn create_deposit(
             &deposit_address,
             None,
             None,
+            usd_price.as_ref(),
+            &request_id.0,
+            idempotency_key.as_ref(),
         )
-        .await
-        .map_err(|e| {
+        .await;
+
+    let deposit = match (deposit, &idempotency_key) {
+        (Ok(deposit), _) => deposit,
+        // A concurrent request with the same key won the race
+        (Err(e), Some(key)) if e.is::<IdempotencyKeyTaken>() => {
+            replay_idempotent_deposit(&state, key)
+                .await?
+                .ok_or_else(|| {
+                    Response::error(
+                        ApiErrorCode::IdempotencyConflict,
+                        "Idempotency key is already in use",
+                    )
+                })?
+        }
+        (Err(e), _) => {
+            return Err(Response::internal_error(ApiErrorCode::DbError, e));
+        }
+    };
+
+    Ok(Response::ok(deposit))
+}
+
+/// A deposit request whose addresses, signer and asset have been checked
+pub(super) struct CheckedDeposit<'a> {
+    pub user_address: Felt,
+    pub token: Felt,
+    pub target_address: Felt,
+    pub asset: &'a Asset,
+}
+
+/// Parses the addresses of a deposit request and checks it against the signer
+/// and the supported assets
+///
+/// The target still has to be checked against the Vesu pools with
+/// [`ensure_vtoken_target`].
+pub(super) fn check_deposit_request<'a>(
+    request: &CreateDepositRequest,
+    signer: Option<Felt>,
+    assets: &'a [Asset],
+) -> Result<CheckedDeposit<'a>, Response<()>> {
+    let user_address = Felt::from_hex(&request.user_address).map_err(|e| {
+        Response::error(
+            ApiErrorCode::InvalidAddress,
+            format!("Invalid user_address: {}", e),
+        )
+    })?;
+
+    ensure_signer(signer, &user_address)?;
+
+    let token = Felt::from_hex(&request.token).map_err(|e| {
+        Response::error(
+            ApiErrorCode::InvalidAddress,
+            format!("Invalid token address: {}", e),
+        )
+    })?;
+
+    let target_address = Felt::from_hex(&request.target_address).map_err(|e| {
+        Response::error(
+            ApiErrorCode::InvalidAddress,
+            format!("Invalid target_address: {}", e),
+        )
+    })?;
+
+    let asset = validate_deposit_request(request, assets)?;
+
+    Ok(CheckedDeposit {
+        user_address,
+        token,
+        target_address,
+        asset,
+    })
+}
+
+/// Checks the action, token and amount of a deposit request
+///
+/// # Returns
+/// The supported asset being deposited
+fn validate_deposit_request<'a>(
+    request: &CreateDepositRequest,
+    assets: &'a [Asset],
+) -> Result<&'a Asset, Response<()>> {
+    if !KNOWN_ACTIONS.contains(&request.action) {
+        return Err(Response::error(
+            ApiErrorCode::UnknownAction,
+            format!("Unknown action: {}", request.action),
+        ));
+    }
+
+    let asset = assets
+        .iter()
+        .find(|asset| same_address(&asset.address, &request.token))
+        .ok_or_else(|| {
             Response::error(
-                format!("Database error: {}", e),
-                StatusCode::INTERNAL_SERVER_ERROR,
+                ApiErrorCode::UnsupportedToken,
+                format!("Unsupported token: {}", request.token),
             )
         })?;
 
-    Ok(Response::ok(deposit))
+    if request.amount <= BigDecimal::zero() {
+        return Err(Response::error(
+            ApiErrorCode::AmountNotPositive,
+            "Amount must be positive",
+        ));
+    }
+    if !request.amount.is_integer() {
+        return Err(Response::error(
+            ApiErrorCode::AmountNotInteger,
+            "Amount must be an integer in base units",
+        ));
+    }
+    if !fits_u256(&request.amount) {
+        return Err(Response::error(
+            ApiErrorCode::AmountTooLarge,
+            "Amount must fit in a u256",
+        ));
+    }
+    if let Some(min_amount) = &asset.min_amount
+        && request.amount < *min_amount
+    {
+        return Err(Response::error(
+            ApiErrorCode::AmountBelowMinimum,
+            format!(
+                "Amount must be at least {} base units of {}",
+                min_amount, asset.symbol
+            ),
+        ));
+    }
+
+    Ok(asset)
+}
+
+/// Checks that the target is a Vesu vToken of the deposited asset
+///
+/// Only vTokens of pools verified by Vesu are accepted, as anyone can create a
+/// pool and list their own contract as its vToken.
+pub(super) fn ensure_vtoken_target(
+    asset: &Asset,
+    target_address: &str,
+    pools: &[VesuPool],
+) -> Result<(), Response<()>> {
+    let allowed = pools
+        .iter()
+        .filter(|pool| pool.is_verified)
+        .flat_map(|pool| &pool.assets)
+        .filter(|pool_asset| same_address(&pool_asset.token.address, &asset.address))
+        .filter_map(|pool_asset| pool_asset.v_token.as_ref())
+        .any(|v_token| same_address(&v_token.address, target_address));
+    if !allowed {
+        return Err(Response::error(
+            ApiErrorCode::InvalidTarget,
+            format!(
+                "target_address is not a vToken of {} in a verified Vesu pool: {}",
+                asset.symbol, target_address
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether an amount fits in a Cairo u256, i.e. is below 2^256
+pub(super) fn fits_u256(amount: &BigDecimal) -> bool {
+    *amount < BigDecimal::from(BigInt::from(1) << 256)
+}
+
+/// Reads the `Idempotency-Key` header, if present
+pub(super) fn parse_idempotency_key(headers: &HeaderMap) -> Result<Option<String>, Response<()>> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+    let key = value
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|key| !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LENGTH)
+        .ok_or_else(|| {
+            Response::error(
+                ApiErrorCode::InvalidIdempotencyKey,
+                format!(
+                    "Idempotency-Key must be 1 to {} visible ASCII characters",
+                    MAX_IDEMPOTENCY_KEY_LENGTH
+                ),
+            )
+        })?;
+    Ok(Some(key.to_string()))
+}
+
+/// Hashes the normalised deposit request, so that equivalent spellings of the
+/// same addresses and amount produce the same fingerprint
+fn deposit_request_hash(
+    user_address: &Felt,
+    request: &CreateDepositRequest,
+    token: &Felt,
+    target_address: &Felt,
+) -> String {
+    let fingerprint = format!(
+        "{:#x}|{}|{}|{:#x}|{:#x}",
+        user_address,
+        request.action,
+        request.amount.normalized(),
+        token,
+        target_address
+    );
+    hex::encode(Sha256::digest(fingerprint.as_bytes()))
+}
+
+/// Looks up the deposit created by an earlier use of an idempotency key
+///
+/// # Returns
+/// * `Ok(None)` if the key was not used before
+/// * `Ok(Some(deposit))` if the key was used with the same request
+/// * 409 if the key was used with a different request
+async fn replay_idempotent_deposit(
+    state: &HandlerState,
+    key: &IdempotencyKey,
+) -> Result<Option<DepositResponse>, Response<()>> {
+    let database_error = |e: eyre::Report| Response::internal_error(ApiErrorCode::DbError, e);
+
+    let Some(record) = state
+        .orderbook
+        .get_idempotency_record(key)
+        .await
+        .map_err(database_error)?
+    else {
+        return Ok(None);
+    };
+
+    if record.request_hash != key.request_hash {
+        return Err(Response::error(
+            ApiErrorCode::IdempotencyConflict,
+            "Idempotency key was already used with a different request",
+        ));
+    }
+
+    state
+        .orderbook
+        .get_deposit(&record.deposit_id)
+        .await
+        .map_err(database_error)
 }
 
 /// Retrieves a specific deposit by its ID
@@ -248,6 +702,16 @@ pub async fn create_deposit(
 ///
 /// # Returns
 /// The deposit details if found, or a 404 error if not found
+#[utoipa::path(
+    get,
+    path = "/deposit/{deposit_id}",
+    tag = "deposits",
+    params(("deposit_id" = String, Path, description = "The 32-byte deposit ID as a hex string")),
+    responses(
+        (status = 200, description = "Deposit", body = Response<DepositResponse>),
+        (status = 404, description = "Deposit not found", body = ErrorResponse)
+    )
+)]
 pub async fn get_deposit(
     State(state): State<Arc<HandlerState>>,
     Path(deposit_id): Path<String>,
@@ -256,65 +720,310 @@ pub async fn get_deposit(
         .orderbook
         .get_deposit(&deposit_id)
         .await
-        .map_err(|e| {
-            Response::error(
-                format!("Database error: {}", e),
-                StatusCode::INTERNAL_SERVER_ERROR,
-            )
-        })?;
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?;
 
     match deposit {
         Some(deposit) => Ok(Response::ok(deposit)),
-        None => Err(Response::error("Deposit not found", StatusCode::NOT_FOUND)),
+        None => Err(Response::error(
+            ApiErrorCode::DepositNotFound,
+            "Deposit not found",
+        )),
     }
 }
 
-/// Retrieves all deposits with "created" status
+/// Retrieves the deposit using a deposit address
+///
+/// The address may be given with or without `0x`, in any case and with
+/// leading zeros, as shown by block explorers.
+///
+/// # Path Parameters
+/// * `deposit_address` - The deposit address as a hex string
 ///
 /// # Returns
-/// A list of all deposits that have status "created"
-pub async fn get_created_deposits(
+/// The deposit details if found, or a 404 error if not found
+#[utoipa::path(
+    get,
+    path = "/deposit/by-address/{deposit_address}",
+    tag = "deposits",
+    params(("deposit_address" = String, Path, description = "The deposit address as a hex string")),
+    responses(
+        (status = 200, description = "Deposit", body = Response<DepositResponse>),
+        (status = 400, description = "Invalid address", body = ErrorResponse),
+        (status = 404, description = "Deposit not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_deposit_by_address(
     State(state): State<Arc<HandlerState>>,
-) -> ApiResult<Vec<DepositResponse>> {
-    let deposits = state
+    Path(deposit_address): Path<String>,
+) -> ApiResult<DepositResponse> {
+    // Deposit addresses are stored canonicalised
+    let deposit_address = canonical_address(&deposit_address)
+        .ok_or_else(|| Response::error(ApiErrorCode::InvalidAddress, "Invalid deposit_address"))?;
+
+    let deposit = state
         .orderbook
-        .get_deposits_by_status("created")
+        .get_deposit_by_address(&deposit_address)
         .await
-        .map_err(|e| {
-            Response::error(
-                format!("Database error: {}", e),
-                StatusCode::INTERNAL_SERVER_ERROR,
-            )
-        })?;
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?;
 
-    Ok(Response::ok(deposits))
+    match deposit {
+        Some(deposit) => Ok(Response::ok(deposit)),
+        None => Err(Response::error(
+            ApiErrorCode::DepositNotFound,
+            "Deposit not found",
+        )),
+    }
 }
 
-/// Retrieves all deposits for a specific user
+/// Streams changes of a deposit as Server-Sent Events
+///
+/// The first `deposit` event carries the current state of the deposit, and a new
+/// event is sent every time its status, `deposit_tx_hash` or `atomiq_swap_id`
+/// changes.
+///
+/// # Path Parameters
+/// * `deposit_id` - The 32-byte deposit ID as a hex string
+#[utoipa::path(
+    get,
+    path = "/deposit/{deposit_id}/events",
+    tag = "deposits",
+    params(("deposit_id" = String, Path, description = "The 32-byte deposit ID as a hex string")),
+    responses(
+        (status = 200, description = "Stream of `deposit` events", body = DepositChange, content_type = "text/event-stream"),
+        (status = 404, description = "Deposit not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_deposit_events(
+    State(state): State<Arc<HandlerState>>,
+    Path(deposit_id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Response<()>> {
+    // Subscribe before reading the current state so no change is missed in between
+    let receiver = state.deposit_changes.subscribe();
+
+    let deposit = state
+        .orderbook
+        .get_deposit(&deposit_id)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?
+        .ok_or_else(|| Response::error(ApiErrorCode::DepositNotFound, "Deposit not found"))?;
+
+    let initial = Some(DepositChange::from(&deposit));
+    let shutdown = state.shutdown.clone();
+    // The stream ends on shutdown so that open connections do not hold up draining
+    let events = stream::unfold(
+        (state, receiver, shutdown, deposit.deposit_id, initial),
+        |(state, mut receiver, mut shutdown, deposit_id, pending)| async move {
+            let change = match pending {
+                Some(change) => change,
+                None => tokio::select! {
+                    change = next_deposit_change(&state, &mut receiver, &deposit_id) => change?,
+                    _ = shutdown::requested(&mut shutdown) => return None,
+                },
+            };
+            Some((
+                deposit_event(&change),
+                (state, receiver, shutdown, deposit_id, None),
+            ))
+        },
+    );
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Waits for the next change of the given deposit
+///
+/// # Returns
+/// `None` once the change feed is closed or the deposit no longer exists
+async fn next_deposit_change(
+    state: &HandlerState,
+    receiver: &mut broadcast::Receiver<DepositChange>,
+    deposit_id: &str,
+) -> Option<DepositChange> {
+    loop {
+        match receiver.recv().await {
+            Ok(change) if change.deposit_id == deposit_id => return Some(change),
+            Ok(_) => continue,
+            // Changes were dropped, resend the current state instead
+            Err(broadcast::error::RecvError::Lagged(_)) => {
+                match state.orderbook.get_deposit(deposit_id).await {
+                    Ok(deposit) => return deposit.as_ref().map(DepositChange::from),
+                    Err(e) => {
+                        error!(deposit_id, "Failed to refetch deposit: {}", e);
+                        continue;
+                    }
+                }
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+/// Builds the `deposit` SSE event for a change
+fn deposit_event(change: &DepositChange) -> Result<Event, Infallible> {
+    Ok(Event::default()
+        .event("deposit")
+        .json_data(change)
+        .unwrap_or_else(|_| Event::default().event("deposit")))
+}
+
+/// Retrieves a page of deposits with "created" status
+///
+/// # Query Parameters
+/// * `cursor`, `limit` and the filters accepted by [`DepositListQuery`].
+///   Any `status` filter is ignored.
+///
+/// # Returns
+/// A page of deposits that have status "created" and the cursor for the next page.
+/// The unversioned alias returns the first page as an array unless `cursor` or
+/// `limit` is given.
+#[utoipa::path(
+    get,
+    path = "/deposits/created",
+    tag = "deposits",
+    params(DepositListQuery),
+    responses(
+        (status = 200, description = "Page of created deposits", body = Response<DepositPage>),
+        (status = 400, description = "Invalid query", body = ErrorResponse)
+    )
+)]
+pub async fn get_created_deposits(
+    State(state): State<Arc<HandlerState>>,
+    legacy: Option<Extension<LegacyAlias>>,
+    Query(query): Query<DepositListQuery>,
+) -> Result<AxumResponse, Response<()>> {
+    let query = DepositListQuery {
+        status: Some(DepositStatus::Created),
+        ..query
+    };
+    deposit_listing(&state, legacy.is_some(), None, query).await
+}
+
+/// Retrieves a page of deposits for a specific user
 ///
 /// # Path Parameters
 /// * `user_address` - The user's wallet address
 ///
+/// # Query Parameters
+/// * `cursor`, `limit` and the filters accepted by [`DepositListQuery`]
+///
 /// # Returns
-/// A list of all deposits for the user
+/// A page of deposits for the user and the cursor for the next page. The
+/// unversioned alias returns the first page as an array unless `cursor` or
+/// `limit` is given.
+#[utoipa::path(
+    get,
+    path = "/deposits/user/{user_address}",
+    tag = "deposits",
+    params(
+        ("user_address" = String, Path, description = "The user's wallet address"),
+        DepositListQuery
+    ),
+    responses(
+        (status = 200, description = "Page of the user's deposits", body = Response<DepositPage>),
+        (status = 400, description = "Invalid query", body = ErrorResponse)
+    )
+)]
 pub async fn get_user_deposits(
     State(state): State<Arc<HandlerState>>,
     Path(user_address): Path<String>,
-) -> ApiResult<Vec<DepositResponse>> {
-    let deposits = state
+    legacy: Option<Extension<LegacyAlias>>,
+    Query(query): Query<DepositListQuery>,
+) -> Result<AxumResponse, Response<()>> {
+    deposit_listing(&state, legacy.is_some(), Some(user_address), query).await
+}
+
+/// Lists deposits for the public listing routes
+///
+/// Requests to an unversioned alias without `cursor` or `limit` get a bare
+/// array, as deployed clients expect. Any other request gets a page.
+async fn deposit_listing(
+    state: &HandlerState,
+    legacy: bool,
+    user_address: Option<String>,
+    query: DepositListQuery,
+) -> Result<AxumResponse, Response<()>> {
+    if legacy && query.cursor.is_none() && query.limit.is_none() {
+        let query = DepositListQuery {
+            limit: Some(MAX_PAGE_LIMIT),
+            ..query
+        };
+        let page = fetch_deposit_page(state, user_address, query).await?;
+        return Ok(legacy_deposit_array(page));
+    }
+
+    let page = fetch_deposit_page(state, user_address, query).await?;
+    Ok(Response::ok(page).into_response())
+}
+
+/// Answers an unversioned alias with the bare array it returned before pagination
+///
+/// The array holds at most [`MAX_PAGE_LIMIT`] deposits. The cursor of the next
+/// page, if any, is sent in the `X-Next-Cursor` header.
+fn legacy_deposit_array(page: DepositPage) -> AxumResponse {
+    let mut response = Response::ok(page.deposits).into_response();
+    if let Some(cursor) = page
+        .next_cursor
+        .and_then(|cursor| HeaderValue::from_str(&cursor).ok())
+    {
+        response.headers_mut().insert(NEXT_CURSOR_HEADER, cursor);
+    }
+    response
+}
+
+/// Validates the listing query and fetches the requested page from the orderbook
+pub(super) async fn list_deposits(
+    state: &HandlerState,
+    user_address: Option<String>,
+    query: DepositListQuery,
+) -> ApiResult<DepositPage> {
+    Ok(Response::ok(
+        fetch_deposit_page(state, user_address, query).await?,
+    ))
+}
+
+/// Fetches the page of deposits described by a listing query
+async fn fetch_deposit_page(
+    state: &HandlerState,
+    user_address: Option<String>,
+    query: DepositListQuery,
+) -> Result<DepositPage, Response<()>> {
+    let cursor = match query.cursor.as_deref() {
+        Some(cursor) => Some(
+            DepositCursor::decode(cursor)
+                .ok_or_else(|| Response::error(ApiErrorCode::InvalidCursor, "Invalid cursor"))?,
+        ),
+        None => None,
+    };
+
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    if !(1..=MAX_PAGE_LIMIT).contains(&limit) {
+        return Err(Response::error(
+            ApiErrorCode::InvalidRequest,
+            format!("limit must be between 1 and {}", MAX_PAGE_LIMIT),
+        ));
+    }
+
+    state
         .orderbook
-        .get_deposits_by_user_address(&user_address)
+        .get_deposits(&deposit_filter(user_address, query), cursor.as_ref(), limit)
         .await
-        .map_err(|e| {
-            Response::error(
-                format!("Database error: {}", e),
-                StatusCode::INTERNAL_SERVER_ERROR,
-            )
-        })?;
-    Ok(Response::ok(deposits))
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))
 }
 
-#[derive(Deserialize)]
+/// Builds the orderbook filter of a listing query
+fn deposit_filter(user_address: Option<String>, query: DepositListQuery) -> DepositFilter {
+    DepositFilter {
+        user_address,
+        status: query.status,
+        token: query.token,
+        action: query.action,
+        target_address: query.target_address,
+        created_after: query.created_after,
+        created_before: query.created_before,
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
 pub struct UpdateAtomiqSwapIdRequest {
     pub atomiq_swap_id: String,
 }
@@ -329,20 +1038,437 @@ pub struct UpdateAtomiqSwapIdRequest {
 ///
 /// # Request Body
 /// * `atomiq_swap_id` - The atomiq swap id to set
+///
+/// When authentication is enabled the request must be signed by the deposit owner.
+#[utoipa::path(
+    post,
+    path = "/deposit/{deposit_id}/atomiq-swap-id",
+    tag = "deposits",
+    params(("deposit_id" = String, Path, description = "The deposit ID")),
+    request_body = UpdateAtomiqSwapIdRequest,
+    security((), ("snip12" = [])),
+    responses(
+        (status = 200, description = "Swap id updated"),
+        (status = 401, description = "Missing or invalid signature", body = ErrorResponse),
+        (status = 403, description = "Signer is not the deposit owner", body = ErrorResponse),
+        (status = 404, description = "Deposit not found", body = ErrorResponse)
+    )
+)]
 pub async fn update_atomiq_swap_id(
     State(state): State<Arc<HandlerState>>,
     Path(deposit_id): Path<String>,
-    Json(request): Json<UpdateAtomiqSwapIdRequest>,
+    SignedRequest {
+        signer,
+        body: request,
+    }: SignedRequest<UpdateAtomiqSwapIdRequest>,
 ) -> ApiResult<()> {
+    if signer.is_some() {
+        let deposit = state
+            .orderbook
+            .get_deposit(&deposit_id)
+            .await
+            .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?
+            .ok_or_else(|| Response::error(ApiErrorCode::DepositNotFound, "Deposit not found"))?;
+        let owner = Felt::from_hex(&deposit.user_address)
+            .map_err(|e| Response::internal_error(ApiErrorCode::Internal, e))?;
+        ensure_signer(signer, &owner)?;
+    }
+
     state
         .orderbook
         .update_atomiq_swap_id(&deposit_id, &request.atomiq_swap_id)
         .await
-        .map_err(|e| {
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?;
+    Ok(Response::ok(()))
+}
+
+/// Cancels a deposit that was never funded
+///
+/// Only deposits in `created` status whose deposit address holds none of the
+/// deposit token can be cancelled. Cancelled deposits are skipped by the vault
+/// watcher, which alerts if funds arrive at their address later.
+///
+/// # Path Parameters
+/// * `deposit_id` - The deposit ID
+///
+/// # Request Body
+/// * `reason` - Optional reason, logged with the cancellation
+///
+/// The request must be signed by the deposit owner, even when authentication
+/// is disabled for the other mutations.
+#[utoipa::path(
+    post,
+    path = "/deposit/{deposit_id}/cancel",
+    tag = "deposits",
+    params(("deposit_id" = String, Path, description = "The deposit ID")),
+    request_body = CancelDepositRequest,
+    security(("snip12" = [])),
+    responses(
+        (status = 200, description = "Cancelled deposit", body = Response<DepositResponse>),
+        (status = 401, description = "Missing or invalid signature", body = ErrorResponse),
+        (status = 403, description = "Signer is not the deposit owner", body = ErrorResponse),
+        (status = 404, description = "Deposit not found", body = ErrorResponse),
+        (status = 409, description = "Deposit is not created or its address holds funds", body = ErrorResponse),
+        (status = 503, description = "Starknet RPC unavailable", body = ErrorResponse)
+    )
+)]
+pub async fn cancel_deposit(
+    State(state): State<Arc<HandlerState>>,
+    Path(deposit_id): Path<String>,
+    OwnerSignedRequest {
+        signer,
+        body: request,
+    }: OwnerSignedRequest<CancelDepositRequest>,
+) -> ApiResult<DepositResponse> {
+    let deposit = state
+        .orderbook
+        .get_deposit(&deposit_id)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?
+        .ok_or_else(|| Response::error(ApiErrorCode::DepositNotFound, "Deposit not found"))?;
+    let owner = Felt::from_hex(&deposit.user_address)
+        .map_err(|e| Response::internal_error(ApiErrorCode::Internal, e))?;
+    ensure_signer(Some(signer), &owner)?;
+
+    if deposit.status != DepositStatus::Created {
+        return Err(Response::error(
+            ApiErrorCode::DepositNotCancellable,
+            format!(
+                "Only created deposits can be cancelled, deposit is {}",
+                deposit.status
+            ),
+        ));
+    }
+
+    let token = Felt::from_hex(&deposit.token)
+        .map_err(|e| Response::internal_error(ApiErrorCode::Internal, e))?;
+    let deposit_address = Felt::from_hex(&deposit.deposit_address)
+        .map_err(|e| Response::internal_error(ApiErrorCode::Internal, e))?;
+    let balance = erc20::balance_of(&state.provider, token, deposit_address)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::RpcUnavailable, e))?;
+    if !balance.is_zero() {
+        return Err(Response::error(
+            ApiErrorCode::DepositNotCancellable,
+            "Deposit address holds funds",
+        ));
+    }
+
+    // A status change since the read above means the deposit is no longer cancellable
+    let deposit = state
+        .orderbook
+        .cancel_deposit(&deposit_id)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?
+        .ok_or_else(|| {
             Response::error(
-                format!("Database error: {}", e),
-                StatusCode::INTERNAL_SERVER_ERROR,
+                ApiErrorCode::DepositNotCancellable,
+                "Deposit status changed while cancelling",
             )
         })?;
-    Ok(Response::ok(()))
+
+    info!(
+        deposit_id,
+        reason = request.reason.as_deref().unwrap_or_default(),
+        "Deposit cancelled"
+    );
+    Ok(Response::ok(deposit))
+}
+
+/// Registers a webhook subscription for the authenticated partner
+///
+/// # Request Body
+/// * `url` - HTTPS endpoint the events are POSTed to; its host must be a public
+///   domain name, not an IP address
+/// * `events` - Events to deliver, any of `deposit.created`, `deposit.initiated`,
+///   `deposit.deposited` and `deposit.cancelled`
+/// * `secret` - Optional signing secret; generated when omitted
+///
+/// # Returns
+/// The created subscription, including the secret used to sign payloads
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    tag = "webhooks",
+    request_body = CreateWebhookRequest,
+    security(("partner_token" = [])),
+    responses(
+        (status = 200, description = "Subscription created", body = Response<CreatedWebhookSubscription>),
+        (status = 400, description = "Invalid URL or events", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorResponse)
+    )
+)]
+pub async fn create_webhook(
+    State(state): State<Arc<HandlerState>>,
+    Partner(partner): Partner,
+    Json(request): Json<CreateWebhookRequest>,
+) -> ApiResult<CreatedWebhookSubscription> {
+    let url = webhooks::parse_webhook_url(&request.url)
+        .map_err(|e| Response::error(ApiErrorCode::InvalidWebhook, e))?;
+
+    if request.events.is_empty() {
+        return Err(Response::error(
+            ApiErrorCode::InvalidWebhook,
+            "At least one event is required",
+        ));
+    }
+    if let Some(event) = request
+        .events
+        .iter()
+        .find(|event| !WEBHOOK_EVENTS.contains(&event.as_str()))
+    {
+        return Err(Response::error(
+            ApiErrorCode::InvalidWebhook,
+            format!("Unsupported event: {}", event),
+        ));
+    }
+
+    let id_bytes: [u8; 16] = rand::random();
+    let id = hex::encode(id_bytes);
+    let secret = match request.secret {
+        Some(secret) if !secret.is_empty() => secret,
+        _ => hex::encode(rand::random::<[u8; 32]>()),
+    };
+
+    let subscription = state
+        .orderbook
+        .create_webhook_subscription(&id, &partner, url.as_str(), &secret, &request.events)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?;
+
+    Ok(Response::ok(CreatedWebhookSubscription {
+        subscription,
+        secret,
+    }))
+}
+
+/// Lists the webhook subscriptions of the authenticated partner
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    tag = "webhooks",
+    security(("partner_token" = [])),
+    responses(
+        (status = 200, description = "Webhook subscriptions", body = Response<Vec<WebhookSubscription>>),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorResponse)
+    )
+)]
+pub async fn get_webhooks(
+    State(state): State<Arc<HandlerState>>,
+    Partner(partner): Partner,
+) -> ApiResult<Vec<WebhookSubscription>> {
+    let subscriptions = state
+        .orderbook
+        .get_webhook_subscriptions(&partner)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?;
+    Ok(Response::ok(subscriptions))
+}
+
+/// Lists the most recent deliveries of a webhook subscription
+///
+/// # Path Parameters
+/// * `subscription_id` - The subscription ID
+///
+/// # Query Parameters
+/// * `status` - Optional delivery status to filter by
+/// * `limit` - Maximum number of deliveries to return
+#[utoipa::path(
+    get,
+    path = "/webhooks/{subscription_id}/deliveries",
+    tag = "webhooks",
+    params(
+        ("subscription_id" = String, Path, description = "The subscription ID"),
+        WebhookDeliveryQuery
+    ),
+    security(("partner_token" = [])),
+    responses(
+        (status = 200, description = "Webhook deliveries", body = Response<Vec<WebhookDelivery>>),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorResponse)
+    )
+)]
+pub async fn get_webhook_deliveries(
+    State(state): State<Arc<HandlerState>>,
+    Partner(partner): Partner,
+    Path(subscription_id): Path<String>,
+    Query(query): Query<WebhookDeliveryQuery>,
+) -> ApiResult<Vec<WebhookDelivery>> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_LIMIT)
+        .clamp(1, MAX_PAGE_LIMIT);
+
+    let deliveries = state
+        .orderbook
+        .get_webhook_deliveries(&partner, &subscription_id, query.status.as_ref(), limit)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?;
+    Ok(Response::ok(deliveries))
+}
+
+/// Queues a webhook delivery for immediate redelivery
+///
+/// The original payload is sent again with the same delivery ID, so receivers can
+/// deduplicate on the `X-OneSat-Delivery` header.
+///
+/// # Path Parameters
+/// * `delivery_id` - The delivery ID
+#[utoipa::path(
+    post,
+    path = "/webhooks/deliveries/{delivery_id}/replay",
+    tag = "webhooks",
+    params(("delivery_id" = i64, Path, description = "The delivery ID")),
+    security(("partner_token" = [])),
+    responses(
+        (status = 200, description = "Delivery queued", body = Response<WebhookDelivery>),
+        (status = 401, description = "Missing or invalid bearer token", body = ErrorResponse),
+        (status = 404, description = "Delivery not found", body = ErrorResponse)
+    )
+)]
+pub async fn replay_webhook_delivery(
+    State(state): State<Arc<HandlerState>>,
+    Partner(partner): Partner,
+    Path(delivery_id): Path<i64>,
+) -> ApiResult<WebhookDelivery> {
+    let delivery = state
+        .orderbook
+        .replay_webhook_delivery(&partner, delivery_id)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?
+        .ok_or_else(|| Response::error(ApiErrorCode::DeliveryNotFound, "Delivery not found"))?;
+    Ok(Response::ok(delivery))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{
+        fixtures::{deposit, vesu_token, wbtc},
+        primitives::VesuPoolAsset,
+    };
+
+    #[tokio::test]
+    async fn test_legacy_deposit_array_carries_next_cursor() {
+        let response = legacy_deposit_array(DepositPage {
+            deposits: vec![deposit()],
+            next_cursor: Some("MTcyODQ3NjA5NjAwMDAwMDpkMQ".to_string()),
+        });
+        assert_eq!(
+            response.headers()[NEXT_CURSOR_HEADER],
+            "MTcyODQ3NjA5NjAwMDAwMDpkMQ"
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["result"][0]["deposit_id"], "d1");
+
+        let response = legacy_deposit_array(DepositPage {
+            deposits: vec![],
+            next_cursor: None,
+        });
+        assert!(!response.headers().contains_key(NEXT_CURSOR_HEADER));
+    }
+
+    #[test]
+    fn test_deposit_request_hash_is_normalised() {
+        let request = |amount: &str| CreateDepositRequest {
+            user_address: "0x0123".to_string(),
+            action: 1,
+            amount: BigDecimal::from_str(amount).unwrap(),
+            token: "0x4".to_string(),
+            target_address: "0x5".to_string(),
+        };
+        let user = Felt::from_hex_unchecked("0x123");
+        let token = Felt::from_hex_unchecked("0x4");
+        let target = Felt::from_hex_unchecked("0x5");
+
+        let hash = deposit_request_hash(&user, &request("100"), &token, &target);
+        assert_eq!(
+            hash,
+            deposit_request_hash(&user, &request("100.00"), &token, &target)
+        );
+        assert_ne!(
+            hash,
+            deposit_request_hash(&user, &request("101"), &token, &target)
+        );
+    }
+
+    #[test]
+    fn test_validate_deposit_request() {
+        let assets = vec![Asset {
+            min_amount: Some(BigDecimal::from(10_000)),
+            ..wbtc()
+        }];
+        let request = |action: u128, token: &str, amount: &str| CreateDepositRequest {
+            user_address: "0x123".to_string(),
+            action,
+            amount: BigDecimal::from_str(amount).unwrap(),
+            token: token.to_string(),
+            target_address: "0xb".to_string(),
+        };
+        let code = |request: CreateDepositRequest| {
+            validate_deposit_request(&request, &assets)
+                .err()
+                .map(|e| e.code)
+        };
+
+        assert_eq!(code(request(1, "0xA", "25000000")), None);
+        assert_eq!(
+            code(request(2, "0xa", "25000000")),
+            Some(Some(ApiErrorCode::UnknownAction))
+        );
+        assert_eq!(
+            code(request(1, "0xc", "25000000")),
+            Some(Some(ApiErrorCode::UnsupportedToken))
+        );
+        assert_eq!(
+            code(request(1, "0xa", "0")),
+            Some(Some(ApiErrorCode::AmountNotPositive))
+        );
+        assert_eq!(
+            code(request(1, "0xa", "25000000.5")),
+            Some(Some(ApiErrorCode::AmountNotInteger))
+        );
+        assert_eq!(
+            code(request(
+                1,
+                "0xa",
+                &(BigInt::from(1) << 256usize).to_string()
+            )),
+            Some(Some(ApiErrorCode::AmountTooLarge))
+        );
+        assert_eq!(
+            code(request(1, "0xa", "9999")),
+            Some(Some(ApiErrorCode::AmountBelowMinimum))
+        );
+    }
+
+    #[test]
+    fn test_vtoken_target_requires_verified_pool() {
+        let pool = |id: &str, is_verified: bool, v_token: &str| VesuPool {
+            id: id.to_string(),
+            address: None,
+            name: id.to_string(),
+            is_verified,
+            assets: vec![VesuPoolAsset {
+                token: vesu_token("0xa", "WBTC", 8),
+                v_token: Some(vesu_token(v_token, "vWBTC", 18)),
+                usd_price: None,
+                stats: None,
+            }],
+        };
+        let pools = vec![pool("0x1", true, "0xb"), pool("0x2", false, "0xe")];
+        let code = |target: &str| {
+            ensure_vtoken_target(&wbtc(), target, &pools)
+                .err()
+                .map(|e| e.code)
+        };
+
+        assert_eq!(code("0x0B"), None);
+        assert_eq!(code("0xe"), Some(Some(ApiErrorCode::InvalidTarget)));
+        assert_eq!(code("0xf"), Some(Some(ApiErrorCode::InvalidTarget)));
+    }
 }
@@ -2,84 +2,135 @@ use std::{net::SocketAddr, sync::Arc};
 
 use axum::{
     Router,
-    http::Method,
-    routing::{get, post},
+    http::{Method, header},
+    middleware,
+    response::Html,
+    routing::get,
 };
+use chrono::{DateTime, Utc};
+use tokio::sync::watch;
 use tower_http::cors::{AllowHeaders, Any, CorsLayer};
 use tracing::info;
 
 use crate::{
-    coingecko::CoingeckoFiatProvider,
-    orderbook::OrderbookProvider,
-    primitives::Asset,
-    registry::VaultRegistry,
-    server::handler::{
-        HandlerState, create_deposit, get_created_deposits, get_deposit, get_health,
-        get_user_deposits, supported_assets, update_atomiq_swap_id, vesu_history, vesu_pools,
-        vesu_positions,
+    metrics::{metrics, track_requests},
+    server::{
+        handler::{HandlerState, get_health, get_ready},
+        openapi::openapi_json,
+        rate_limit::{RateLimiter, rate_limit},
+        request_id::{REQUEST_ID_HEADER, request_id},
+        versioning::{
+            DEPRECATION_HEADER, LegacyRoutes, NEXT_CURSOR_HEADER, SUNSET_HEADER, legacy_alias,
+        },
     },
+    shutdown,
 };
 
-mod handler;
+mod admin;
+pub mod auth;
+mod batch;
+mod export;
+pub mod handler;
+pub mod openapi;
+mod portfolio;
+mod quote;
+pub mod rate_limit;
+pub mod request_id;
+mod stats;
+mod util;
+mod v1;
+mod verify;
+pub mod versioning;
 
 pub struct Server {
     pub port: u16,
     pub handler_state: Arc<HandlerState>,
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// When the unversioned route aliases may be removed
+    pub legacy_sunset: DateTime<Utc>,
+    /// Page served at `/docs`, absent when the API reference is disabled
+    pub docs_html: Option<String>,
 }
 
 impl Server {
     pub fn new(
         port: u16,
-        coingecko: Arc<CoingeckoFiatProvider>,
-        supported_assets: Vec<Asset>,
-        vesu_api_base_url: String,
-        vault_registry: Arc<VaultRegistry>,
-        orderbook: Arc<OrderbookProvider>,
+        handler_state: HandlerState,
+        rate_limiter: Option<Arc<RateLimiter>>,
+        legacy_sunset: DateTime<Utc>,
+        docs_html: Option<String>,
     ) -> Self {
-        let handler_state = Arc::new(HandlerState {
-            coingecko,
-            supported_assets,
-            vesu_api_base_url,
-            vault_registry,
-            orderbook,
-        });
         Self {
             port,
-            handler_state,
+            handler_state: Arc::new(handler_state),
+            rate_limiter,
+            legacy_sunset,
+            docs_html,
         }
     }
 
-    pub async fn run(&self) {
+    /// Serves requests until shutdown is requested on `shutdown`, then stops
+    /// accepting connections and returns once in-flight requests have completed
+    pub async fn run(&self, mut shutdown: watch::Receiver<bool>) {
         let cors = CorsLayer::new()
             .allow_methods(vec![Method::GET, Method::POST])
             .allow_origin(Any)
-            .allow_headers(AllowHeaders::any());
+            .allow_headers(AllowHeaders::any())
+            .expose_headers([
+                REQUEST_ID_HEADER,
+                DEPRECATION_HEADER,
+                SUNSET_HEADER,
+                NEXT_CURSOR_HEADER,
+                header::LINK,
+            ]);
 
+        // Routes that predate /v1 are also served unversioned, for deployed clients
+        let legacy_routes = Arc::new(LegacyRoutes::new(self.legacy_sunset));
         let app = Router::new()
             .route("/health", get(get_health))
-            .route("/assets", get(supported_assets))
-            .route("/deposit", post(create_deposit))
-            .route("/deposit/:deposit_id", get(get_deposit))
-            .route("/deposits/created", get(get_created_deposits))
-            .route("/deposits/user/:user_address", get(get_user_deposits))
-            .route(
-                "/deposit/:deposit_id/atomiq-swap-id",
-                post(update_atomiq_swap_id),
-            )
-            .nest(
-                "/vesu",
-                Router::new()
-                    .route("/positions", get(vesu_positions))
-                    .route("/history", get(vesu_history))
-                    .route("/pools", get(vesu_pools)),
-            )
+            .route("/ready", get(get_ready))
+            .route("/metrics", get(metrics))
+            .route("/openapi.json", get(openapi_json))
+            .nest(v1::PREFIX, v1::router())
+            .merge(
+                v1::legacy_router()
+                    .layer(middleware::from_fn_with_state(legacy_routes, legacy_alias)),
+            );
+        let app = match &self.docs_html {
+            Some(docs_html) => {
+                let docs_html = Html(docs_html.clone());
+                app.route("/docs", get(|| async move { docs_html }))
+            }
+            None => app,
+        };
+
+        let app = match &self.rate_limiter {
+            Some(rate_limiter) => app.layer(middleware::from_fn_with_state(
+                Arc::clone(rate_limiter),
+                rate_limit,
+            )),
+            None => app,
+        };
+
+        // Outermost so that rate-limited and CORS-rejected requests are counted
+        // and logged with their request ID too
+        let app = app
             .layer(cors)
+            .layer(middleware::from_fn(track_requests))
+            .layer(middleware::from_fn(request_id))
             .with_state(Arc::clone(&self.handler_state));
 
         let addr = SocketAddr::from(([0, 0, 0, 0], self.port));
         info!("Listening on http://{}", addr);
 
         let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-        axum::serve(listener, app).await.unwrap();
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(async move { shutdown::requested(&mut shutdown).await })
+        .await
+        .unwrap();
+        info!("Server stopped");
     }
 }
@@ -0,0 +1,205 @@
+use axum::Json;
+use eyre::{Result, bail};
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+};
+
+use crate::{
+    server::{
+        admin, auth::SIGNATURE_HEADER, batch, export, handler, portfolio, quote, stats, verify,
+    },
+    settings::DocsSettings,
+};
+
+/// Page rendering the OpenAPI document with the Scalar API reference viewer
+///
+/// The bundle runs on our origin, so the browser only executes it if it matches
+/// the configured integrity hash.
+const DOCS_HTML: &str = r#"<!doctype html>
+<html>
+  <head>
+    <title>OneSat API</title>
+    <meta charset="utf-8" />
+    <meta name="viewport" content="width=device-width, initial-scale=1" />
+  </head>
+  <body>
+    <script id="api-reference" data-url="/openapi.json"></script>
+    <script src="{script_url}" integrity="{script_integrity}" crossorigin="anonymous"></script>
+  </body>
+</html>
+"#;
+
+/// OpenAPI document of the OneSat API, derived from the handler annotations
+///
+/// Only the versioned routes are documented; their unversioned aliases are deprecated.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "OneSat API", description = "Deposits, assets and Vesu protocol integration for OneSat"),
+    paths(handler::get_health, handler::get_ready),
+    nest((path = "/v1", api = V1Doc)),
+    modifiers(&Snip12SecurityAddon),
+    tags(
+        (name = "health", description = "Service health"),
+        (name = "auth", description = "SNIP-12 signed request authentication"),
+        (name = "assets", description = "Supported assets and prices"),
+        (name = "deposits", description = "Deposit creation and tracking"),
+        (name = "vesu", description = "Vesu protocol proxy"),
+        (name = "portfolio", description = "Deposits and Vesu positions valued in USD"),
+        (name = "stats", description = "Volume deposited through OneSat"),
+        (name = "webhooks", description = "Partner webhooks for deposit lifecycle events"),
+        (name = "admin", description = "Operator tools for stuck deposits, with an audit trail"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Routes of version 1 of the API, nested under `/v1`
+#[derive(OpenApi)]
+#[openapi(paths(
+    handler::get_auth_nonce,
+    handler::supported_assets,
+    handler::create_deposit,
+    batch::create_deposit_batch,
+    handler::get_deposit,
+    handler::get_deposit_by_address,
+    handler::get_deposit_events,
+    verify::verify_deposit,
+    handler::get_created_deposits,
+    handler::get_user_deposits,
+    export::export_user_deposits,
+    handler::update_atomiq_swap_id,
+    handler::cancel_deposit,
+    handler::vesu_positions,
+    handler::vesu_history,
+    handler::vesu_pools,
+    portfolio::get_portfolio,
+    quote::get_quote,
+    stats::get_stats,
+    stats::get_stats_timeseries,
+    handler::create_webhook,
+    handler::get_webhooks,
+    handler::get_webhook_deliveries,
+    handler::replay_webhook_delivery,
+    admin::admin_list_deposits,
+    admin::admin_force_deposit_status,
+    admin::admin_requeue_deposit,
+    admin::admin_recover_deposit,
+    admin::admin_deposit_audit_log,
+))]
+struct V1Doc;
+
+/// Registers the SNIP-12 signature headers, partner and admin tokens as security schemes
+struct Snip12SecurityAddon;
+
+impl Modify for Snip12SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "snip12",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                SIGNATURE_HEADER,
+                "SNIP-12 signature, sent together with the X-Starknet-Account, \
+                 X-Starknet-Nonce and X-Starknet-Expiry headers. \
+                 Required only when authentication is enabled.",
+            ))),
+        );
+        components.add_security_scheme(
+            "partner_token",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("Partner token configured in `webhooks.partners`"))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "admin_token",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("Operator token configured in `admin.operators`"))
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// Serves the OpenAPI document
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Renders the interactive API reference page for the configured Scalar bundle
+///
+/// Fails if the bundle is not served over HTTPS or the integrity hash is not a
+/// SHA-256, SHA-384 or SHA-512 Subresource Integrity hash.
+pub fn docs_html(settings: &DocsSettings) -> Result<String> {
+    if !settings.script_url.starts_with("https://") {
+        bail!("docs.script_url must use https");
+    }
+    let is_sri_hash = ["sha256-", "sha384-", "sha512-"].iter().any(|algorithm| {
+        settings
+            .script_integrity
+            .strip_prefix(algorithm)
+            .is_some_and(|hash| {
+                !hash.is_empty()
+                    && hash
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '='))
+            })
+    });
+    if !is_sri_hash {
+        bail!("docs.script_integrity must be a Subresource Integrity hash, e.g. sha384-<base64>");
+    }
+    if settings.script_url.contains(['"', '<', '>']) {
+        bail!("docs.script_url is not a valid attribute value");
+    }
+
+    Ok(DOCS_HTML
+        .replace("{script_url}", &settings.script_url)
+        .replace("{script_integrity}", &settings.script_integrity))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_docs_html_requires_a_pinned_integrity_checked_bundle() {
+        let settings = |script_url: &str, script_integrity: &str| DocsSettings {
+            script_url: script_url.to_string(),
+            script_integrity: script_integrity.to_string(),
+        };
+        let url = "https://cdn.example/npm/@scalar/api-reference@1.0.0/standalone.js";
+
+        let html = docs_html(&settings(url, "sha384-AbC+/9=")).unwrap();
+        assert!(html.contains(&format!(
+            r#"<script src="{}" integrity="sha384-AbC+/9=" crossorigin="anonymous">"#,
+            url
+        )));
+
+        assert!(docs_html(&settings("http://cdn.example/a.js", "sha384-AbC")).is_err());
+        assert!(docs_html(&settings(url, "")).is_err());
+        assert!(docs_html(&settings(url, "md5-AbC")).is_err());
+        assert!(docs_html(&settings(url, "sha384-\"><script>")).is_err());
+    }
+
+    #[test]
+    fn test_openapi_document_lists_routes() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let paths = doc["paths"].as_object().unwrap();
+        for path in [
+            "/health",
+            "/v1/deposit",
+            "/v1/deposit/{deposit_id}",
+            "/v1/deposits/user/{user_address}",
+            "/v1/vesu/pools",
+            "/v1/webhooks",
+            "/v1/admin/deposits",
+        ] {
+            assert!(paths.contains_key(path), "missing {}", path);
+        }
+        assert!(!paths.contains_key("/deposit"));
+        assert!(doc["components"]["schemas"]["DepositResponse"].is_object());
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use axum::extract::{Path, State};
+use bigdecimal::{BigDecimal, Zero};
+use starknet::core::types::Felt;
+use tracing::warn;
+
+use crate::{
+    primitives::{
+        ApiErrorCode, ApiResult, Asset, DepositResponse, ErrorResponse, PendingDeposit, Portfolio,
+        PortfolioPool, PortfolioPosition, Response, VesuPool, VesuPosition, VesuTokenAmount,
+    },
+    server::{
+        handler::HandlerState,
+        util::{from_base_units, same_address},
+    },
+};
+
+/// Returns the user's deposits and Vesu positions valued in USD
+///
+/// Combines the user's Vesu positions, their deposits that have not reached
+/// Vesu yet, and the cached Coingecko prices of the supported assets. Tokens
+/// that are not supported assets are valued at the price reported by Vesu.
+#[utoipa::path(
+    get,
+    path = "/portfolio/{user_address}",
+    tag = "portfolio",
+    params(("user_address" = String, Path, description = "The user's wallet address")),
+    responses(
+        (status = 200, description = "The user's portfolio", body = Response<Portfolio>),
+        (status = 400, description = "Invalid address", body = ErrorResponse),
+        (status = 502, description = "Vesu or price provider failed", body = ErrorResponse)
+    )
+)]
+pub async fn get_portfolio(
+    State(state): State<Arc<HandlerState>>,
+    Path(user_address): Path<String>,
+) -> ApiResult<Portfolio> {
+    Felt::from_hex(&user_address).map_err(|e| {
+        Response::error(
+            ApiErrorCode::InvalidAddress,
+            format!("Invalid user_address: {}", e),
+        )
+    })?;
+
+    let (positions, pending_deposits, prices) = tokio::join!(
+        state.vesu.positions(&user_address),
+        state.orderbook.get_pending_deposits(&user_address),
+        state.coingecko.get_all_prices(),
+    );
+    let positions = positions?;
+    let pending_deposits =
+        pending_deposits.map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?;
+    let prices =
+        prices.map_err(|e| Response::internal_error(ApiErrorCode::UpstreamPriceFailed, e))?;
+
+    // Pools are only needed to attribute pending deposits, so a failure only
+    // loses that attribution
+    let pools = if pending_deposits.is_empty() {
+        Arc::default()
+    } else {
+        state.vesu.pools(None).await.unwrap_or_else(|e| {
+            warn!("Failed to fetch Vesu pools for portfolio: {}", e);
+            Arc::default()
+        })
+    };
+
+    let valuation = Valuation {
+        assets: &state.supported_assets,
+        prices: &prices,
+    };
+    Ok(Response::ok(valuation.portfolio(
+        user_address,
+        positions,
+        pending_deposits,
+        &pools,
+    )))
+}
+
+/// Prices tokens using the supported assets and their cached prices
+struct Valuation<'a> {
+    assets: &'a [Asset],
+    prices: &'a HashMap<String, BigDecimal>,
+}
+
+impl Valuation<'_> {
+    fn portfolio(
+        &self,
+        user_address: String,
+        positions: Vec<VesuPosition>,
+        pending_deposits: Vec<DepositResponse>,
+        pools: &[VesuPool],
+    ) -> Portfolio {
+        let mut by_pool: BTreeMap<String, PortfolioPool> = BTreeMap::new();
+
+        let positions: Vec<PortfolioPosition> = positions
+            .into_iter()
+            .map(|position| {
+                let usd_value = self.position_value(&position);
+                let pool = by_pool
+                    .entry(position.pool_id.clone())
+                    .or_insert_with(|| empty_pool(&position.pool_id, Some(&position.pool_name)));
+                pool.positions += 1;
+                if let Some(usd_value) = &usd_value {
+                    pool.usd_value += usd_value;
+                }
+                PortfolioPosition {
+                    position,
+                    usd_value,
+                }
+            })
+            .collect();
+
+        let pending_deposits: Vec<PendingDeposit> = pending_deposits
+            .into_iter()
+            .map(|deposit| {
+                let pool = pools.iter().find(|pool| {
+                    pool.assets.iter().any(|asset| {
+                        asset.v_token.as_ref().is_some_and(|v_token| {
+                            same_address(&v_token.address, &deposit.target_address)
+                        })
+                    })
+                });
+                let asset = self
+                    .assets
+                    .iter()
+                    .find(|asset| same_address(&asset.address, &deposit.token));
+                let token_amount =
+                    asset.map(|asset| from_base_units(&deposit.amount, asset.decimals.into()));
+                let usd_value = token_amount
+                    .as_ref()
+                    .zip(self.price(&deposit.token, None))
+                    .map(|(amount, price)| (amount * price).normalized());
+
+                if let Some(pool) = pool {
+                    let entry = by_pool
+                        .entry(pool.id.clone())
+                        .or_insert_with(|| empty_pool(&pool.id, Some(&pool.name)));
+                    if let Some(usd_value) = &usd_value {
+                        entry.pending_usd_value += usd_value;
+                    }
+                }
+
+                PendingDeposit {
+                    deposit,
+                    pool_id: pool.map(|pool| pool.id.clone()),
+                    token_amount,
+                    usd_value,
+                }
+            })
+            .collect();
+
+        Portfolio {
+            user_address,
+            total_usd_value: sum(positions.iter().map(|position| &position.usd_value)),
+            pending_usd_value: sum(pending_deposits.iter().map(|deposit| &deposit.usd_value)),
+            positions,
+            pending_deposits,
+            pools: by_pool.into_values().collect(),
+        }
+    }
+
+    /// Collateral value minus debt value, if every involved token is priced
+    fn position_value(&self, position: &VesuPosition) -> Option<BigDecimal> {
+        let collateral = self.amount_value(&position.collateral)?;
+        let debt = match &position.debt {
+            Some(debt) => self.amount_value(debt)?,
+            None => BigDecimal::zero(),
+        };
+        Some((collateral - debt).normalized())
+    }
+
+    fn amount_value(&self, amount: &VesuTokenAmount) -> Option<BigDecimal> {
+        self.price(&amount.token.address, amount.usd_price.as_ref())
+            .map(|price| &amount.amount * price)
+    }
+
+    /// The cached price of a supported asset, or `fallback` for other tokens
+    fn price<'b>(
+        &'b self,
+        token: &str,
+        fallback: Option<&'b BigDecimal>,
+    ) -> Option<&'b BigDecimal> {
+        match self
+            .assets
+            .iter()
+            .find(|asset| same_address(&asset.address, token))
+        {
+            Some(asset) => self.prices.get(&asset.coingecko_id),
+            None => fallback,
+        }
+    }
+}
+
+fn empty_pool(pool_id: &str, pool_name: Option<&str>) -> PortfolioPool {
+    PortfolioPool {
+        pool_id: pool_id.to_string(),
+        pool_name: pool_name.map(str::to_string),
+        positions: 0,
+        usd_value: BigDecimal::zero(),
+        pending_usd_value: BigDecimal::zero(),
+    }
+}
+
+fn sum<'a>(values: impl Iterator<Item = &'a Option<BigDecimal>>) -> BigDecimal {
+    values
+        .flatten()
+        .fold(BigDecimal::zero(), |total, value| total + value)
+        .normalized()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::{decimal, deposit, vesu_token as token, wbtc},
+        primitives::{VesuPoolAsset, VesuPositionType, VesuTokenAmount},
+    };
+
+    #[test]
+    fn test_portfolio_valuation() {
+        let assets = vec![wbtc()];
+        let prices = HashMap::from([("bitcoin".to_string(), decimal("60000"))]);
+        let valuation = Valuation {
+            assets: &assets,
+            prices: &prices,
+        };
+
+        let positions = vec![VesuPosition {
+            position_type: VesuPositionType::Borrow,
+            pool_id: "0x1".to_string(),
+            pool_name: "Prime".to_string(),
+            collateral: VesuTokenAmount {
+                token: token("0xA", "WBTC", 8),
+                amount: decimal("0.5"),
+                // Ignored in favour of the cached price
+                usd_price: Some(decimal("1")),
+                usd_value: None,
+            },
+            collateral_shares: None,
+            debt: Some(VesuTokenAmount {
+                token: token("0xc", "USDC", 6),
+                amount: decimal("1000"),
+                usd_price: Some(decimal("1")),
+                usd_value: None,
+            }),
+            is_deprecated: false,
+        }];
+        let pending = vec![deposit()];
+        let pools = vec![VesuPool {
+            id: "0x2".to_string(),
+            address: None,
+            name: "Re7".to_string(),
+            is_verified: true,
+            assets: vec![VesuPoolAsset {
+                token: token("0xa", "WBTC", 8),
+                v_token: Some(token("0x0b", "vWBTC", 18)),
+                usd_price: None,
+                stats: None,
+            }],
+        }];
+
+        let portfolio = valuation.portfolio("0x123".to_string(), positions, pending, &pools);
+
+        assert_eq!(portfolio.total_usd_value.to_string(), "29000");
+        assert_eq!(portfolio.pending_usd_value.to_string(), "15000");
+        let deposit = &portfolio.pending_deposits[0];
+        assert_eq!(deposit.token_amount.as_ref().unwrap().to_string(), "0.25");
+        assert_eq!(deposit.pool_id.as_deref(), Some("0x2"));
+        assert_eq!(portfolio.pools.len(), 2);
+        assert_eq!(portfolio.pools[0].usd_value.to_string(), "29000");
+        assert_eq!(portfolio.pools[1].pending_usd_value.to_string(), "15000");
+    }
+}
@@ -0,0 +1,225 @@
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use bigdecimal::{BigDecimal, RoundingMode, Zero, num_bigint::BigInt};
+use starknet::core::types::Felt;
+
+use crate::{
+    erc4626,
+    primitives::{ApiErrorCode, ApiResult, Asset, ErrorResponse, Quote, QuoteQuery, Response},
+    server::{
+        handler::{HandlerState, fits_u256},
+        util::{from_base_units, same_address},
+    },
+};
+
+/// Quotes what a deposit into a Vesu pool yields
+///
+/// Converts the source amount into the target token at the cached Coingecko
+/// prices, previews the vToken shares minted for it with the ERC-4626
+/// `preview_deposit` call, and adds the pool's supply APY and the estimated
+/// relayer fee for deploying the deposit vault.
+#[utoipa::path(
+    get,
+    path = "/quote",
+    tag = "deposits",
+    params(QuoteQuery),
+    responses(
+        (status = 200, description = "Quote", body = Response<Quote>),
+        (status = 400, description = "Unsupported asset, token not listed in the pool or amount too large", body = ErrorResponse),
+        (status = 404, description = "Pool not found on Vesu", body = ErrorResponse),
+        (status = 502, description = "Vesu or price provider failed", body = ErrorResponse),
+        (status = 503, description = "Starknet RPC unavailable", body = ErrorResponse)
+    )
+)]
+pub async fn get_quote(
+    State(state): State<Arc<HandlerState>>,
+    Query(query): Query<QuoteQuery>,
+) -> ApiResult<Quote> {
+    if query.amount <= BigDecimal::zero() {
+        return Err(Response::error(
+            ApiErrorCode::AmountNotPositive,
+            "Amount must be positive",
+        ));
+    }
+    // Rejects absurd amounts before they are scaled; the converted amount is checked below
+    if !fits_u256(&query.amount) {
+        return Err(amount_too_large());
+    }
+
+    let source = find_asset(&state.supported_assets, &query.source_asset).ok_or_else(|| {
+        Response::error(
+            ApiErrorCode::InvalidRequest,
+            format!("Unsupported source_asset: {}", query.source_asset),
+        )
+    })?;
+    let target = state
+        .supported_assets
+        .iter()
+        .find(|asset| same_address(&asset.address, &query.target_token))
+        .ok_or_else(|| {
+            Response::error(
+                ApiErrorCode::InvalidRequest,
+                format!("Unsupported target_token: {}", query.target_token),
+            )
+        })?;
+
+    let (prices, pools) = tokio::join!(
+        state.coingecko.get_all_prices(),
+        state.vesu.pools(Some(&query.pool_id)),
+    );
+    let prices =
+        prices.map_err(|e| Response::internal_error(ApiErrorCode::UpstreamPriceFailed, e))?;
+    let price_of = |asset: &Asset| {
+        prices.get(&asset.coingecko_id).cloned().ok_or_else(|| {
+            Response::error(
+                ApiErrorCode::UpstreamPriceFailed,
+                format!("No price available for {}", asset.symbol),
+            )
+        })
+    };
+    let source_price = price_of(source)?;
+    let target_price = price_of(target)?;
+
+    let pools = pools?;
+    let pool_asset = pools
+        .iter()
+        .flat_map(|pool| &pool.assets)
+        .find(|asset| same_address(&asset.token.address, &target.address))
+        .ok_or_else(|| {
+            Response::error(
+                ApiErrorCode::InvalidRequest,
+                format!("{} is not listed in pool {}", target.symbol, query.pool_id),
+            )
+        })?;
+    let v_token = pool_asset.v_token.as_ref().ok_or_else(|| {
+        Response::error(
+            ApiErrorCode::InvalidRequest,
+            format!("{} has no vToken in pool {}", target.symbol, query.pool_id),
+        )
+    })?;
+    let v_token_address = Felt::from_hex(&v_token.address)
+        .map_err(|e| Response::internal_error(ApiErrorCode::UpstreamVesuFailed, e))?;
+
+    let token_amount = convert(
+        &query.amount,
+        &source_price,
+        &target_price,
+        target.decimals.into(),
+    )
+    .ok_or_else(|| Response::error(ApiErrorCode::UpstreamPriceFailed, "Target price is zero"))?;
+    let token_amount_base_units = to_base_units(&token_amount, target.decimals.into());
+    if !fits_u256(&token_amount_base_units) {
+        return Err(amount_too_large());
+    }
+
+    let (shares, deploy_fee) = tokio::join!(
+        erc4626::preview_deposit(&state.provider, v_token_address, &token_amount_base_units),
+        state.deploy_fees.estimate(),
+    );
+    let shares = shares.map_err(|e| Response::internal_error(ApiErrorCode::RpcUnavailable, e))?;
+
+    // The fee is valued at the price of the supported asset with the fee token's symbol
+    let deploy_fee = deploy_fee.map(|mut fee| {
+        fee.usd_value = state
+            .supported_assets
+            .iter()
+            .find(|asset| asset.symbol.eq_ignore_ascii_case(&fee.token))
+            .and_then(|asset| prices.get(&asset.coingecko_id))
+            .map(|price| (&fee.amount * price).normalized());
+        fee
+    });
+
+    let stats = pool_asset.stats.as_ref();
+    Ok(Response::ok(Quote {
+        source_asset: source.symbol.clone(),
+        source_amount: query.amount,
+        source_price,
+        target_token: target.address.clone(),
+        target_symbol: target.symbol.clone(),
+        target_price,
+        token_amount,
+        token_amount_base_units,
+        pool_id: query.pool_id,
+        v_token: v_token.address.clone(),
+        shares: from_base_units(&shares, v_token.decimals),
+        supply_apy: stats.and_then(|stats| stats.supply_apy.clone()),
+        reward_apr: stats.and_then(|stats| stats.reward_apr.clone()),
+        deploy_fee,
+    }))
+}
+
+/// Error for an amount whose target token amount does not fit in a u256
+fn amount_too_large() -> Response<()> {
+    Response::error(
+        ApiErrorCode::AmountTooLarge,
+        "Amount converted to base units of the target token must fit in a u256",
+    )
+}
+
+/// Finds a supported asset by symbol (case-insensitive) or address
+fn find_asset<'a>(assets: &'a [Asset], symbol_or_address: &str) -> Option<&'a Asset> {
+    assets
+        .iter()
+        .find(|asset| asset.symbol.eq_ignore_ascii_case(symbol_or_address))
+        .or_else(|| {
+            assets
+                .iter()
+                .find(|asset| same_address(&asset.address, symbol_or_address))
+        })
+}
+
+/// Converts an amount between assets at their USD prices, rounded down to `decimals`
+fn convert(
+    amount: &BigDecimal,
+    source_price: &BigDecimal,
+    target_price: &BigDecimal,
+    decimals: u32,
+) -> Option<BigDecimal> {
+    if target_price.is_zero() {
+        return None;
+    }
+    Some(
+        (amount * source_price / target_price)
+            .with_scale_round(decimals as i64, RoundingMode::Down)
+            .normalized(),
+    )
+}
+
+/// Converts an amount in token units to whole base units
+fn to_base_units(amount: &BigDecimal, decimals: u32) -> BigDecimal {
+    (amount * BigDecimal::from(BigInt::from(10).pow(decimals)))
+        .with_scale_round(0, RoundingMode::Down)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_convert_rounds_down_to_target_decimals() {
+        let amount = BigDecimal::from_str("0.01").unwrap();
+        let btc = BigDecimal::from_str("60000").unwrap();
+        let usdc = BigDecimal::from_str("0.9999").unwrap();
+
+        let token_amount = convert(&amount, &btc, &usdc, 6).unwrap();
+        assert_eq!(token_amount.to_string(), "600.060006");
+        assert_eq!(to_base_units(&token_amount, 6).to_string(), "600060006");
+        assert!(convert(&amount, &btc, &BigDecimal::zero(), 6).is_none());
+    }
+
+    #[test]
+    fn test_converted_amount_must_fit_in_u256() {
+        let max = BigDecimal::from(BigInt::from(1) << 256) - 1;
+        assert!(fits_u256(&max));
+
+        // Fits in source units, but not in the 18 decimals of the target token
+        let amount = BigDecimal::from(BigInt::from(1) << 200);
+        assert!(fits_u256(&amount));
+        let token_amount =
+            convert(&amount, &BigDecimal::from(1), &BigDecimal::from(1), 18).unwrap();
+        assert!(!fits_u256(&to_base_units(&token_amount, 18)));
+    }
+}
@@ -0,0 +1,292 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, MatchedPath, Request, State},
+    http::{HeaderMap, HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response as AxumResponse},
+};
+use moka::future::Cache;
+use starknet::core::types::Felt;
+
+use crate::{
+    primitives::{ApiErrorCode, Response},
+    server::versioning::unversioned_route,
+    settings::{RateLimitBudget, RateLimitSettings},
+};
+
+/// Maximum number of buckets kept in memory
+const RATE_LIMIT_CACHE_SIZE: u64 = 100_000;
+
+/// Buckets untouched for this long are evicted; they would be full again anyway
+const RATE_LIMIT_IDLE_SECS: u64 = 3600;
+
+/// A token bucket replenished continuously at a fixed rate
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(budget: &RateLimitBudget) -> Self {
+        Self {
+            tokens: budget.capacity as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes a token from the bucket
+    ///
+    /// # Returns
+    /// * `Ok(())` if a token was available
+    /// * `Err(wait)` with the time until the next token becomes available
+    fn try_acquire(&mut self, budget: &RateLimitBudget, now: Instant) -> Result<(), Duration> {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * budget.refill_per_sec).min(budget.capacity as f64);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+
+        if budget.refill_per_sec <= 0.0 {
+            return Err(Duration::from_secs(RATE_LIMIT_IDLE_SECS));
+        }
+        Err(Duration::from_secs_f64(
+            (1.0 - self.tokens) / budget.refill_per_sec,
+        ))
+    }
+}
+
+/// Token-bucket rate limiter keyed by route and client
+///
+/// Every request consumes a token from the bucket of its client IP. Once its
+/// SNIP-12 signature is verified, it also consumes a token from the signer's
+/// bucket, so a single user cannot exhaust the budget by rotating IPs.
+/// Unverified input such as a `user_address` path parameter, the
+/// `X-Starknet-Account` header or the body is never used as the user, as anyone
+/// could then drain another user's budget.
+pub struct RateLimiter {
+    default_budget: RateLimitBudget,
+    route_budgets: HashMap<String, RateLimitBudget>,
+    trust_forwarded_for: bool,
+    buckets: Cache<String, Arc<Mutex<TokenBucket>>>,
+}
+
+impl RateLimiter {
+    /// Creates a new RateLimiter from settings
+    pub fn new(settings: RateLimitSettings) -> Self {
+        let buckets = Cache::builder()
+            .max_capacity(RATE_LIMIT_CACHE_SIZE)
+            .time_to_idle(Duration::from_secs(RATE_LIMIT_IDLE_SECS))
+            .build();
+        Self {
+            default_budget: settings.default,
+            route_budgets: settings.routes,
+            trust_forwarded_for: settings.trust_forwarded_for,
+            buckets,
+        }
+    }
+
+    /// Returns the budget configured for a route, falling back to the default
+    fn budget(&self, route: &str) -> RateLimitBudget {
+        self.route_budgets
+            .get(route)
+            .copied()
+            .unwrap_or(self.default_budget)
+    }
+
+    /// Takes a token for the given route and client key
+    async fn check(&self, route: &str, key: &str) -> Result<(), Duration> {
+        let budget = self.budget(route);
+        let bucket = self
+            .buckets
+            .get_with(format!("{}|{}", route, key), async {
+                Arc::new(Mutex::new(TokenBucket::new(&budget)))
+            })
+            .await;
+        let mut bucket = bucket.lock().expect("rate limit bucket poisoned");
+        bucket.try_acquire(&budget, Instant::now())
+    }
+
+    /// Resolves the client IP, honouring X-Forwarded-For only when configured to
+    ///
+    /// Clients can send their own X-Forwarded-For entries, so only the last
+    /// one, appended by the trusted proxy, is used.
+    fn client_ip(&self, headers: &HeaderMap, peer: SocketAddr) -> IpAddr {
+        if self.trust_forwarded_for {
+            let forwarded = headers
+                .get("x-forwarded-for")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.rsplit(',').next())
+                .and_then(|value| value.trim().parse::<IpAddr>().ok());
+            if let Some(ip) = forwarded {
+                return ip;
+            }
+        }
+        peer.ip()
+    }
+}
+
+/// The user budget of a route, charged by the signature extractors once the
+/// signer of the request is verified
+#[derive(Clone)]
+pub struct SignerRateLimit {
+    limiter: Arc<RateLimiter>,
+    route: String,
+}
+
+impl SignerRateLimit {
+    /// Takes a token from the bucket of the verified signer
+    pub async fn check(&self, signer: &Felt) -> Result<(), AxumResponse> {
+        self.limiter
+            .check(&self.route, &format!("user:{:#x}", signer))
+            .await
+            .map_err(too_many_requests)
+    }
+}
+
+/// Middleware enforcing the per-route rate limits
+pub async fn rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    matched_path: Option<MatchedPath>,
+    mut request: Request,
+    next: Next,
+) -> AxumResponse {
+    // Versions of a route and its unversioned alias share a budget
+    let route = format!(
+        "{} {}",
+        request.method(),
+        unversioned_route(
+            matched_path
+                .as_ref()
+                .map(|path| path.as_str())
+                .unwrap_or_else(|| request.uri().path())
+        )
+    );
+
+    let ip = limiter.client_ip(request.headers(), peer);
+    if let Err(wait) = limiter.check(&route, &format!("ip:{}", ip)).await {
+        return too_many_requests(wait);
+    }
+
+    request
+        .extensions_mut()
+        .insert(SignerRateLimit { limiter, route });
+
+    next.run(request).await
+}
+
+fn too_many_requests(wait: Duration) -> AxumResponse {
+    let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+    let mut response =
+        Response::<()>::error(ApiErrorCode::RateLimited, "Too many requests").into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_refills() {
+        let budget = RateLimitBudget {
+            capacity: 2,
+            refill_per_sec: 1.0,
+        };
+        let start = Instant::now();
+        let mut bucket = TokenBucket {
+            tokens: 2.0,
+            last_refill: start,
+        };
+
+        assert!(bucket.try_acquire(&budget, start).is_ok());
+        assert!(bucket.try_acquire(&budget, start).is_ok());
+        let wait = bucket.try_acquire(&budget, start).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+
+        assert!(
+            bucket
+                .try_acquire(&budget, start + Duration::from_secs(1))
+                .is_ok()
+        );
+        // Refill never exceeds capacity
+        assert!(
+            bucket
+                .try_acquire(&budget, start + Duration::from_secs(100))
+                .is_ok()
+        );
+        assert!(
+            bucket
+                .try_acquire(&budget, start + Duration::from_secs(100))
+                .is_ok()
+        );
+        assert!(
+            bucket
+                .try_acquire(&budget, start + Duration::from_secs(100))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_client_ip_uses_the_proxy_entry() {
+        let limiter = RateLimiter::new(RateLimitSettings {
+            default: RateLimitBudget {
+                capacity: 1,
+                refill_per_sec: 0.0,
+            },
+            routes: HashMap::new(),
+            trust_forwarded_for: true,
+        });
+        let peer: SocketAddr = "10.0.0.1:443".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("1.2.3.4, 203.0.113.7"),
+        );
+        assert_eq!(
+            limiter.client_ip(&headers, peer),
+            "203.0.113.7".parse::<IpAddr>().unwrap()
+        );
+
+        headers.insert("x-forwarded-for", HeaderValue::from_static("garbage"));
+        assert_eq!(limiter.client_ip(&headers, peer), peer.ip());
+    }
+
+    #[tokio::test]
+    async fn test_signer_budget_is_per_verified_signer() {
+        let limiter = Arc::new(RateLimiter::new(RateLimitSettings {
+            default: RateLimitBudget {
+                capacity: 1,
+                refill_per_sec: 0.0,
+            },
+            routes: HashMap::new(),
+            trust_forwarded_for: false,
+        }));
+        let rate_limit = SignerRateLimit {
+            limiter,
+            route: "POST /deposit".to_string(),
+        };
+        let victim = Felt::from_hex_unchecked("0x123");
+        let other = Felt::from_hex_unchecked("0x456");
+
+        assert!(rate_limit.check(&other).await.is_ok());
+        let response = rate_limit.check(&other).await.unwrap_err();
+        assert_eq!(response.status(), axum::http::StatusCode::TOO_MANY_REQUESTS);
+        // Another signer exhausting its budget leaves the victim's untouched
+        assert!(rate_limit.check(&victim).await.is_ok());
+    }
+}
@@ -0,0 +1,114 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, MatchedPath, Request},
+    http::{HeaderName, HeaderValue, request::Parts},
+    middleware::Next,
+    response::Response as AxumResponse,
+};
+use tracing::Instrument;
+
+/// Header carrying the ID of a request, accepted from clients and echoed back
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Maximum length of a client-provided request ID
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// ID correlating the logs of a request, and of the deposit it created
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Takes the client's request ID if it is usable, otherwise generates one
+    fn from_header(value: Option<&HeaderValue>) -> Self {
+        let provided = value
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| is_valid_request_id(value));
+        match provided {
+            Some(value) => Self(value.to_string()),
+            None => {
+                let id_bytes: [u8; 16] = rand::random();
+                Self(hex::encode(id_bytes))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for RequestId
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        // Only absent when a route is served without the middleware
+        Ok(parts
+            .extensions
+            .get::<RequestId>()
+            .cloned()
+            .unwrap_or_else(|| RequestId::from_header(parts.headers.get(REQUEST_ID_HEADER))))
+    }
+}
+
+/// Checks that a client-provided request ID is short and safe to log
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LENGTH
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
+/// Middleware assigning every request an ID and a tracing span
+///
+/// The ID is taken from the `X-Request-Id` header when the client sends a
+/// usable one, and returned in the `X-Request-Id` response header. Everything
+/// logged while handling the request, including upstream calls, is recorded
+/// in a `request` span carrying the ID.
+pub async fn request_id(mut request: Request, next: Next) -> AxumResponse {
+    let request_id = RequestId::from_header(request.headers().get(REQUEST_ID_HEADER));
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id.0,
+        method = %request.method(),
+        route,
+    );
+
+    let header = HeaderValue::from_str(&request_id.0).ok();
+    request.extensions_mut().insert(request_id);
+    let mut response = next.run(request).instrument(span).await;
+    if let Some(header) = header {
+        response.headers_mut().insert(REQUEST_ID_HEADER, header);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_id_from_header() {
+        let provided = HeaderValue::from_static("app-7f0c2a4e:retry.1");
+        assert_eq!(
+            RequestId::from_header(Some(&provided)).0,
+            "app-7f0c2a4e:retry.1"
+        );
+
+        let generated = RequestId::from_header(None).0;
+        assert_eq!(generated.len(), 32);
+
+        let unsafe_id = HeaderValue::from_static("id\" injected=1");
+        assert_ne!(
+            RequestId::from_header(Some(&unsafe_id)).0,
+            "id\" injected=1"
+        );
+        let long_id = HeaderValue::from_str(&"a".repeat(MAX_REQUEST_ID_LENGTH + 1)).unwrap();
+        assert_eq!(RequestId::from_header(Some(&long_id)).0.len(), 32);
+    }
+}
@@ -0,0 +1,303 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use axum::extract::{Query, State};
+use bigdecimal::{BigDecimal, Zero};
+
+use crate::{
+    orderbook::stats::BucketTotals,
+    primitives::{
+        ApiErrorCode, ApiResult, Asset, DEFAULT_STATS_BUCKETS, DepositStats, DepositStatus,
+        DepositStatusCounts, ErrorResponse, MAX_STATS_BUCKETS, Response, StatsBucket,
+        StatsTimeseries, StatsTimeseriesQuery, TokenVolume,
+    },
+    server::{
+        handler::HandlerState,
+        util::{address_key, from_base_units, same_address},
+    },
+};
+
+/// Returns the volume deposited through OneSat
+///
+/// Counts deposits per status and distinct users, and values the volume of
+/// `deposited` deposits at the cached Coingecko prices of the supported
+/// assets. The database aggregates are cached, so they may lag behind by up
+/// to the configured cache TTL, as reported by `as_of`.
+#[utoipa::path(
+    get,
+    path = "/stats",
+    tag = "stats",
+    responses(
+        (status = 200, description = "Deposit statistics", body = Response<DepositStats>),
+        (status = 500, description = "Database error", body = ErrorResponse),
+        (status = 502, description = "Price provider failed", body = ErrorResponse)
+    )
+)]
+pub async fn get_stats(State(state): State<Arc<HandlerState>>) -> ApiResult<DepositStats> {
+    let (totals, prices) = tokio::join!(state.stats.totals(), state.coingecko.get_all_prices());
+    let totals = totals.map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?;
+    let prices =
+        prices.map_err(|e| Response::internal_error(ApiErrorCode::UpstreamPriceFailed, e))?;
+
+    let mut status_counts = DepositStatusCounts::default();
+    for totals in &totals.data.by_token_status {
+        let count = match totals.status {
+            DepositStatus::Created => &mut status_counts.created,
+            DepositStatus::Initiated => &mut status_counts.initiated,
+            DepositStatus::Deposited => &mut status_counts.deposited,
+            DepositStatus::Cancelled => &mut status_counts.cancelled,
+        };
+        *count += totals.deposits;
+    }
+
+    let valuation = VolumeValuation {
+        assets: &state.supported_assets,
+        prices: &prices,
+    };
+    let volume = valuation.volumes(
+        totals
+            .data
+            .by_token_status
+            .iter()
+            .filter(|totals| totals.status == DepositStatus::Deposited)
+            .map(|totals| (totals.token.as_str(), totals.deposits, &totals.amount)),
+    );
+
+    Ok(Response::ok(DepositStats {
+        total_deposits: status_counts.created
+            + status_counts.initiated
+            + status_counts.deposited
+            + status_counts.cancelled,
+        status_counts,
+        unique_users: totals.data.unique_users,
+        tvl_usd: total_usd_value(&volume),
+        volume,
+        as_of: totals.as_of,
+    }))
+}
+
+/// Returns the deposits created over time
+///
+/// Aggregates deposits by creation time into UTC buckets of the requested
+/// interval, including buckets without deposits, and values the volume of
+/// `deposited` deposits at the current cached Coingecko prices. The database
+/// aggregates are cached like those of `GET /stats`.
+#[utoipa::path(
+    get,
+    path = "/stats/timeseries",
+    tag = "stats",
+    params(StatsTimeseriesQuery),
+    responses(
+        (status = 200, description = "Deposit statistics over time", body = Response<StatsTimeseries>),
+        (status = 400, description = "Invalid interval or limit", body = ErrorResponse),
+        (status = 500, description = "Database error", body = ErrorResponse),
+        (status = 502, description = "Price provider failed", body = ErrorResponse)
+    )
+)]
+pub async fn get_stats_timeseries(
+    State(state): State<Arc<HandlerState>>,
+    Query(query): Query<StatsTimeseriesQuery>,
+) -> ApiResult<StatsTimeseries> {
+    let interval = query.interval.unwrap_or_default();
+    let limit = query.limit.unwrap_or(DEFAULT_STATS_BUCKETS);
+    if !(1..=MAX_STATS_BUCKETS).contains(&limit) {
+        return Err(Response::error(
+            ApiErrorCode::InvalidRequest,
+            format!("limit must be between 1 and {}", MAX_STATS_BUCKETS),
+        ));
+    }
+
+    let (timeseries, prices) = tokio::join!(
+        state.stats.timeseries(interval, limit),
+        state.coingecko.get_all_prices()
+    );
+    let timeseries = timeseries.map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?;
+    let prices =
+        prices.map_err(|e| Response::internal_error(ApiErrorCode::UpstreamPriceFailed, e))?;
+
+    let valuation = VolumeValuation {
+        assets: &state.supported_assets,
+        prices: &prices,
+    };
+    Ok(Response::ok(StatsTimeseries {
+        interval,
+        buckets: valuation.buckets(&timeseries.data),
+        as_of: timeseries.as_of,
+    }))
+}
+
+/// Values deposited volumes using the supported assets and their cached prices
+struct VolumeValuation<'a> {
+    assets: &'a [Asset],
+    prices: &'a HashMap<String, BigDecimal>,
+}
+
+impl VolumeValuation<'_> {
+    /// Merges the totals of each token into one volume, largest USD value first
+    ///
+    /// Tokens are merged by address, so that addresses stored with different
+    /// case or leading zeros count as one token.
+    fn volumes<'b>(
+        &self,
+        totals: impl Iterator<Item = (&'b str, i64, &'b BigDecimal)>,
+    ) -> Vec<TokenVolume> {
+        let mut by_token: BTreeMap<String, (i64, BigDecimal)> = BTreeMap::new();
+        for (token, deposits, amount) in totals {
+            let entry = by_token
+                .entry(address_key(token))
+                .or_insert_with(|| (0, BigDecimal::zero()));
+            entry.0 += deposits;
+            entry.1 += amount;
+        }
+
+        let mut volumes: Vec<TokenVolume> = by_token
+            .into_iter()
+            .map(|(token, (deposits, amount))| {
+                let asset = self
+                    .assets
+                    .iter()
+                    .find(|asset| same_address(&asset.address, &token));
+                let token_amount =
+                    asset.map(|asset| from_base_units(&amount, asset.decimals.into()));
+                let usd_value = token_amount
+                    .as_ref()
+                    .zip(asset.and_then(|asset| self.prices.get(&asset.coingecko_id)))
+                    .map(|(amount, price)| (amount * price).normalized());
+                TokenVolume {
+                    token,
+                    symbol: asset.map(|asset| asset.symbol.clone()),
+                    deposits,
+                    amount: amount.normalized(),
+                    token_amount,
+                    usd_value,
+                }
+            })
+            .collect();
+
+        // Unpriced tokens last
+        volumes.sort_by(|a, b| match (&a.usd_value, &b.usd_value) {
+            (Some(a), Some(b)) => b.cmp(a),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+        volumes
+    }
+
+    /// Groups the per-token rows of each bucket into one bucket
+    fn buckets(&self, totals: &[BucketTotals]) -> Vec<StatsBucket> {
+        totals
+            .chunk_by(|a, b| a.bucket == b.bucket)
+            .map(|rows| {
+                let volume = self.volumes(rows.iter().filter_map(|row| {
+                    let token = row.token.as_deref().filter(|_| row.deposited > 0)?;
+                    Some((token, row.deposited, &row.deposited_amount))
+                }));
+                StatsBucket {
+                    start: rows[0].bucket,
+                    deposits: rows.iter().map(|row| row.deposits).sum(),
+                    deposited: rows.iter().map(|row| row.deposited).sum(),
+                    usd_volume: total_usd_value(&volume),
+                    volume,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Total USD value of the volumes that could be priced
+fn total_usd_value(volumes: &[TokenVolume]) -> BigDecimal {
+    volumes
+        .iter()
+        .filter_map(|volume| volume.usd_value.as_ref())
+        .sum::<BigDecimal>()
+        .normalized()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::fixtures::{asset, decimal};
+
+    #[test]
+    fn test_volumes_merge_tokens_and_value_them() {
+        let assets = vec![
+            asset("WBTC", "wbtc", "0x3fe2", 8),
+            asset("USDC", "usdc", "0x53c9", 6),
+        ];
+        let prices = HashMap::from([
+            ("wbtc".to_string(), BigDecimal::from(60_000)),
+            ("usdc".to_string(), BigDecimal::from(1)),
+        ]);
+        let valuation = VolumeValuation {
+            assets: &assets,
+            prices: &prices,
+        };
+
+        let wbtc = BigDecimal::from(50_000_000);
+        let wbtc_again = BigDecimal::from(25_000_000);
+        let usdc = BigDecimal::from(1_500_000);
+        let unknown = BigDecimal::from(7);
+        let volumes = valuation.volumes(
+            [
+                ("0x3fe2", 2, &wbtc),
+                ("0x53c9", 1, &usdc),
+                ("0x0003FE2", 1, &wbtc_again),
+                ("0x99", 3, &unknown),
+            ]
+            .into_iter(),
+        );
+
+        assert_eq!(volumes.len(), 3);
+        assert_eq!(volumes[0].token, "0x3fe2");
+        assert_eq!(volumes[0].deposits, 3);
+        assert_eq!(volumes[0].amount, BigDecimal::from(75_000_000));
+        assert_eq!(volumes[0].token_amount, Some(decimal("0.75")));
+        assert_eq!(volumes[0].usd_value, Some(BigDecimal::from(45_000)));
+        assert_eq!(volumes[1].symbol.as_deref(), Some("USDC"));
+        assert_eq!(volumes[1].usd_value, Some(decimal("1.5")));
+        assert_eq!(volumes[2].token, "0x99");
+        assert_eq!(volumes[2].usd_value, None);
+        assert_eq!(total_usd_value(&volumes), decimal("45001.5"));
+    }
+
+    #[test]
+    fn test_buckets_group_rows_and_keep_empty_buckets() {
+        let assets = vec![asset("USDC", "usdc", "0x53c9", 6)];
+        let prices = HashMap::from([("usdc".to_string(), BigDecimal::from(1))]);
+        let valuation = VolumeValuation {
+            assets: &assets,
+            prices: &prices,
+        };
+        let day = |day| Utc.with_ymd_and_hms(2026, 10, day, 0, 0, 0).unwrap();
+        let row = |bucket, token: Option<&str>, deposits, deposited, amount: i64| BucketTotals {
+            bucket,
+            token: token.map(str::to_string),
+            deposits,
+            deposited,
+            deposited_amount: BigDecimal::from(amount),
+        };
+
+        let buckets = valuation.buckets(&[
+            row(day(15), Some("0x53c9"), 3, 2, 2_000_000),
+            row(day(15), Some("0x77"), 1, 0, 0),
+            row(day(16), None, 0, 0, 0),
+        ]);
+
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].start, day(15));
+        assert_eq!(buckets[0].deposits, 4);
+        assert_eq!(buckets[0].deposited, 2);
+        assert_eq!(buckets[0].volume.len(), 1);
+        assert_eq!(buckets[0].usd_volume, BigDecimal::from(2));
+        assert_eq!(buckets[1].start, day(16));
+        assert_eq!(buckets[1].deposits, 0);
+        assert!(buckets[1].volume.is_empty());
+        assert_eq!(buckets[1].usd_volume, BigDecimal::zero());
+    }
+}
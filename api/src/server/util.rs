@@ -0,0 +1,65 @@
+//! Helpers shared by the route handlers
+
+use bigdecimal::{BigDecimal, num_bigint::BigInt};
+use starknet::core::types::Felt;
+
+/// Converts an amount in base units to token units
+pub fn from_base_units(amount: &BigDecimal, decimals: u32) -> BigDecimal {
+    (amount * BigDecimal::new(BigInt::from(1), decimals as i64)).normalized()
+}
+
+/// Formats an address as a felt: lowercase, with a `0x` prefix and without
+/// leading zeros
+///
+/// # Returns
+/// `None` if the address is not a valid felt
+pub fn canonical_address(address: &str) -> Option<String> {
+    let digits = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .unwrap_or(address);
+    Felt::from_hex(&format!("0x{}", digits))
+        .ok()
+        .map(|felt| format!("{:#x}", felt))
+}
+
+/// Key under which spellings of the same address are grouped
+///
+/// The [`canonical_address`] of valid addresses, and the lowercase form of
+/// invalid ones so that they can still be compared.
+pub fn address_key(address: &str) -> String {
+    canonical_address(address).unwrap_or_else(|| address.to_lowercase())
+}
+
+/// Compares two addresses as felts, ignoring case and leading zeros
+pub fn same_address(a: &str, b: &str) -> bool {
+    address_key(a) == address_key(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::decimal;
+
+    #[test]
+    fn test_from_base_units() {
+        assert_eq!(from_base_units(&decimal("25000000"), 8), decimal("0.25"));
+        assert_eq!(from_base_units(&decimal("1000000"), 6).to_string(), "1");
+    }
+
+    #[test]
+    fn test_canonical_address() {
+        for address in ["0x00ABc", "0X0abc", "abc", "0xabc"] {
+            assert_eq!(canonical_address(address).as_deref(), Some("0xabc"));
+        }
+        assert_eq!(canonical_address("0xnot-hex"), None);
+        assert_eq!(canonical_address(""), None);
+    }
+
+    #[test]
+    fn test_same_address() {
+        assert!(same_address("0x0A", "0xa"));
+        assert!(!same_address("0xa", "0xb"));
+        assert!(same_address("NotAnAddress", "notanaddress"));
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use axum::{
+    Router,
+    routing::{get, post},
+};
+
+use crate::server::{
+    admin::{
+        admin_deposit_audit_log, admin_force_deposit_status, admin_list_deposits,
+        admin_recover_deposit, admin_requeue_deposit,
+    },
+    batch::create_deposit_batch,
+    export::export_user_deposits,
+    handler::{
+        HandlerState, cancel_deposit, create_deposit, create_webhook, get_auth_nonce,
+        get_created_deposits, get_deposit, get_deposit_by_address, get_deposit_events,
+        get_user_deposits, get_webhook_deliveries, get_webhooks, replay_webhook_delivery,
+        supported_assets, update_atomiq_swap_id, vesu_history, vesu_pools, vesu_positions,
+    },
+    portfolio::get_portfolio,
+    quote::get_quote,
+    stats::{get_stats, get_stats_timeseries},
+    verify::verify_deposit,
+};
+
+/// Path prefix of version 1 of the API
+pub const PREFIX: &str = "/v1";
+
+/// Routes of version 1 of the API, relative to [`PREFIX`]
+///
+/// A later version gets its own module with its own router, so it can change
+/// request and response types without affecting v1 clients.
+pub fn router() -> Router<Arc<HandlerState>> {
+    legacy_router()
+        .route("/auth/nonce", get(get_auth_nonce))
+        .route(
+            "/deposit/by-address/:deposit_address",
+            get(get_deposit_by_address),
+        )
+        .route("/deposit/:deposit_id/events", get(get_deposit_events))
+        .route("/deposit/:deposit_id/cancel", post(cancel_deposit))
+        .route("/deposit/:deposit_id/verify", get(verify_deposit))
+        .route("/deposits/batch", post(create_deposit_batch))
+        .route(
+            "/deposits/user/:user_address/export",
+            get(export_user_deposits),
+        )
+        .route("/quote", get(get_quote))
+        .route("/portfolio/:user_address", get(get_portfolio))
+        .route("/stats", get(get_stats))
+        .route("/stats/timeseries", get(get_stats_timeseries))
+        .route("/webhooks", get(get_webhooks).post(create_webhook))
+        .route(
+            "/webhooks/:subscription_id/deliveries",
+            get(get_webhook_deliveries),
+        )
+        .route(
+            "/webhooks/deliveries/:delivery_id/replay",
+            post(replay_webhook_delivery),
+        )
+        .nest(
+            "/admin",
+            Router::new()
+                .route("/deposits", get(admin_list_deposits))
+                .route(
+                    "/deposits/:deposit_id/status",
+                    post(admin_force_deposit_status),
+                )
+                .route("/deposits/:deposit_id/requeue", post(admin_requeue_deposit))
+                .route("/deposits/:deposit_id/recover", post(admin_recover_deposit))
+                .route("/deposits/:deposit_id/audit", get(admin_deposit_audit_log)),
+        )
+}
+
+/// Routes of version 1 that were served before the API was versioned
+///
+/// Also served without the prefix as deprecated aliases for deployed clients.
+/// Routes added since are only served under [`PREFIX`].
+pub fn legacy_router() -> Router<Arc<HandlerState>> {
+    Router::new()
+        .route("/assets", get(supported_assets))
+        .route("/deposit", post(create_deposit))
+        .route("/deposit/:deposit_id", get(get_deposit))
+        .route("/deposits/created", get(get_created_deposits))
+        .route("/deposits/user/:user_address", get(get_user_deposits))
+        .route(
+            "/deposit/:deposit_id/atomiq-swap-id",
+            post(update_atomiq_swap_id),
+        )
+        .nest(
+            "/vesu",
+            Router::new()
+                .route("/positions", get(vesu_positions))
+                .route("/history", get(vesu_history))
+                .route("/pools", get(vesu_pools)),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes_do_not_conflict() {
+        // Building the router panics on overlapping routes
+        let _ = router();
+        let _ = Router::new().nest(PREFIX, router()).merge(legacy_router());
+    }
+}
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use starknet::core::types::Felt;
+
+use crate::{
+    primitives::{ApiErrorCode, ApiResult, DepositVerification, ErrorResponse, Response},
+    registry::AddressPrediction,
+    server::handler::HandlerState,
+};
+
+/// Verifies the address of a deposit
+///
+/// Recomputes the deposit address from the stored deposit parameters, both
+/// locally with the registry's hashing scheme and with the registry's
+/// `predict_address`, and returns the salt, constructor calldata, class hash
+/// and registry address used, so that clients can check the address
+/// themselves before funding it.
+///
+/// # Path Parameters
+/// * `deposit_id` - The 32-byte deposit ID as a hex string
+///
+/// # Returns
+/// The inputs of the address computation, the computed addresses and whether
+/// they match the stored one
+#[utoipa::path(
+    get,
+    path = "/deposit/{deposit_id}/verify",
+    tag = "deposits",
+    params(("deposit_id" = String, Path, description = "The 32-byte deposit ID as a hex string")),
+    responses(
+        (status = 200, description = "Deposit address verification", body = Response<DepositVerification>),
+        (status = 404, description = "Deposit not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+        (status = 503, description = "Starknet RPC unavailable", body = ErrorResponse)
+    )
+)]
+pub async fn verify_deposit(
+    State(state): State<Arc<HandlerState>>,
+    Path(deposit_id): Path<String>,
+) -> ApiResult<DepositVerification> {
+    let deposit = state
+        .orderbook
+        .get_deposit(&deposit_id)
+        .await
+        .map_err(|e| Response::internal_error(ApiErrorCode::DbError, e))?
+        .ok_or_else(|| Response::error(ApiErrorCode::DepositNotFound, "Deposit not found"))?;
+
+    let prediction = AddressPrediction {
+        user: stored_felt(&deposit.user_address)?,
+        deposit_id: stored_felt(&deposit.deposit_id)?,
+        action: deposit.action,
+        amount: deposit.amount.clone(),
+        token: stored_felt(&deposit.token)?,
+        target: stored_felt(&deposit.target_address)?,
+    };
+    let deposit_address = stored_felt(&deposit.deposit_address)?;
+
+    let registry = &state.vault_registry;
+    let (class_hash, predicted_address) = tokio::join!(
+        registry.uda_class_hash(),
+        registry.predict_address(
+            &prediction.user,
+            &prediction.deposit_id,
+            prediction.action,
+            &prediction.amount,
+            &prediction.token,
+            &prediction.target,
+        ),
+    );
+    let class_hash =
+        class_hash.map_err(|e| Response::internal_error(ApiErrorCode::RpcUnavailable, e))?;
+    let predicted_address =
+        predicted_address.map_err(|e| Response::internal_error(ApiErrorCode::RpcUnavailable, e))?;
+
+    let computed = prediction
+        .compute_address(registry.address, class_hash)
+        .map_err(|e| Response::internal_error(ApiErrorCode::Internal, e))?;
+
+    Ok(Response::ok(DepositVerification {
+        deposit_id: deposit.deposit_id,
+        deposit_address: deposit.deposit_address,
+        registry_address: format!("{:#x}", registry.address),
+        class_hash: format!("{:#x}", class_hash),
+        salt: format!("{:#x}", computed.salt),
+        constructor_calldata: computed
+            .constructor_calldata
+            .iter()
+            .map(|value| format!("{:#x}", value))
+            .collect(),
+        computed_address: format!("{:#x}", computed.address),
+        predicted_address: format!("{:#x}", predicted_address),
+        matches: computed.address == predicted_address && deposit_address == predicted_address,
+    }))
+}
+
+/// Parses a value stored with a deposit as a Felt
+fn stored_felt(value: &str) -> Result<Felt, Response<()>> {
+    Felt::from_hex(value).map_err(|e| Response::internal_error(ApiErrorCode::Internal, e))
+}
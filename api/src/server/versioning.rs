@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue, header},
+    middleware::Next,
+    response::Response as AxumResponse,
+};
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::server::v1;
+
+/// Header announcing that a route is deprecated (RFC 9745)
+pub const DEPRECATION_HEADER: HeaderName = HeaderName::from_static("deprecation");
+
+/// Header announcing when a route stops being served (RFC 8594)
+pub const SUNSET_HEADER: HeaderName = HeaderName::from_static("sunset");
+
+/// Header carrying the cursor of the next page on the legacy deposit listings
+pub const NEXT_CURSOR_HEADER: HeaderName = HeaderName::from_static("x-next-cursor");
+
+/// Unix time at which the unversioned routes were deprecated in favour of `/v1`
+const LEGACY_DEPRECATED_AT: i64 = 1_792_195_200;
+
+/// Unix time after which the unversioned routes may be removed, unless configured
+const DEFAULT_LEGACY_SUNSET: i64 = 1_807_920_000;
+
+/// Deprecation headers sent on the unversioned aliases of the v1 routes
+pub struct LegacyRoutes {
+    deprecation: HeaderValue,
+    sunset: HeaderValue,
+}
+
+impl LegacyRoutes {
+    /// Creates the headers for aliases removed after `sunset`
+    pub fn new(sunset: DateTime<Utc>) -> Self {
+        let sunset = sunset
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string()
+            .parse()
+            .expect("HTTP date is a valid header value");
+        Self {
+            deprecation: HeaderValue::from_str(&format!("@{}", LEGACY_DEPRECATED_AT))
+                .expect("structured date is a valid header value"),
+            sunset,
+        }
+    }
+
+    /// Sunset used when none is configured
+    pub fn default_sunset() -> DateTime<Utc> {
+        Utc.timestamp_opt(DEFAULT_LEGACY_SUNSET, 0)
+            .single()
+            .expect("valid timestamp")
+    }
+}
+
+/// Request extension set on requests to an unversioned alias
+///
+/// Lets handlers keep response shapes that predate `/v1` for deployed clients.
+#[derive(Clone, Copy)]
+pub struct LegacyAlias;
+
+/// Middleware marking responses of unversioned aliases as deprecated
+///
+/// Adds `Deprecation` and `Sunset` headers, and a `Link` to the same route
+/// under `/v1`. Handlers see the request as a [`LegacyAlias`].
+pub async fn legacy_alias(
+    State(legacy): State<Arc<LegacyRoutes>>,
+    mut request: Request,
+    next: Next,
+) -> AxumResponse {
+    let successor = format!(
+        "<{}{}>; rel=\"successor-version\"",
+        v1::PREFIX,
+        request.uri().path()
+    );
+
+    request.extensions_mut().insert(LegacyAlias);
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(DEPRECATION_HEADER, legacy.deprecation.clone());
+    headers.insert(SUNSET_HEADER, legacy.sunset.clone());
+    if let Ok(successor) = HeaderValue::from_str(&successor) {
+        headers.append(header::LINK, successor);
+    }
+    response
+}
+
+/// Strips the version prefix from a route path, e.g. `/v1/deposit` to `/deposit`
+///
+/// Lets per-route settings apply to every version of a route and to its
+/// unversioned alias.
+pub fn unversioned_route(path: &str) -> &str {
+    let Some(rest) = path.strip_prefix("/v") else {
+        return path;
+    };
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let rest = &rest[digits..];
+    if digits == 0 || !(rest.is_empty() || rest.starts_with('/')) {
+        return path;
+    }
+    if rest.is_empty() { "/" } else { rest }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unversioned_route() {
+        assert_eq!(unversioned_route("/v1/deposit"), "/deposit");
+        assert_eq!(
+            unversioned_route("/v12/deposit/:deposit_id"),
+            "/deposit/:deposit_id"
+        );
+        assert_eq!(unversioned_route("/v1"), "/");
+        assert_eq!(unversioned_route("/deposit"), "/deposit");
+        assert_eq!(unversioned_route("/vesu/pools"), "/vesu/pools");
+        assert_eq!(unversioned_route("/v1x/pools"), "/v1x/pools");
+    }
+
+    #[test]
+    fn test_legacy_headers() {
+        let legacy = LegacyRoutes::new(LegacyRoutes::default_sunset());
+        assert_eq!(legacy.deprecation, "@1792195200");
+        assert_eq!(legacy.sunset, "Sat, 17 Apr 2027 00:00:00 GMT");
+    }
+}
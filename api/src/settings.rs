@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::{fs::File, io::BufReader};
+use std::{collections::HashMap, fs::File, io::BufReader};
 
 use crate::primitives::Asset;
 
@@ -13,6 +13,93 @@ pub struct CoingeckoSettings {
     pub cache_ttl_secs: Option<u64>,
 }
 
+#[derive(Serialize, Deserialize, Default)]
+pub struct AuthSettings {
+    // Whether deposit mutations must carry a SNIP-12 signature
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce_ttl_secs: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct RateLimitBudget {
+    // Maximum number of requests that can be made in a burst
+    pub capacity: u32,
+    // Number of requests replenished per second
+    pub refill_per_sec: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RateLimitSettings {
+    // Budget applied to routes without an explicit entry in `routes`
+    pub default: RateLimitBudget,
+    // Per-route budgets keyed by "<METHOD> <route path>", e.g. "POST /deposit"
+    #[serde(default)]
+    pub routes: HashMap<String, RateLimitBudget>,
+    // Whether to take the client IP from the X-Forwarded-For header (only behind a trusted proxy)
+    #[serde(default)]
+    pub trust_forwarded_for: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ReadinessSettings {
+    // Chain ID the RPC must report, as a short string ("SN_MAIN") or hex, also used
+    // to sign and verify; defaults to the first chain ID the RPC reports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_chain_id: Option<String>,
+    // Maximum age of a cached price before the asset is reported stale
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_price_age_secs: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct VesuSettings {
+    // Request timeout in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    // Retries after a failed request (5xx, 429, timeout or connection error)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+    // Seconds pool data is served from cache without revalidation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pools_fresh_secs: Option<u64>,
+    // Seconds stale pool data may be served while it is revalidated in the background
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pools_stale_secs: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WebhookSettings {
+    // Bearer tokens allowed to manage webhook subscriptions, keyed by partner name
+    pub partners: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_interval_secs: Option<u64>,
+    // Number of delivery attempts before a delivery is moved to dead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AdminSettings {
+    // Bearer tokens allowed to use the admin API, keyed by operator name
+    pub operators: HashMap<String, String>,
+    // Account that sends recover() to deposit addresses; recovery is disabled when absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DocsSettings {
+    // Scalar API reference bundle loaded by /docs, pinned to an exact version, e.g.
+    // "https://cdn.jsdelivr.net/npm/@scalar/api-reference@<version>/dist/browser/standalone.js"
+    pub script_url: String,
+    // Subresource Integrity hash of that bundle, e.g. "sha384-<base64>"
+    pub script_integrity: String,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Settings {
     // Port number on which the server will listen
@@ -23,12 +110,43 @@ pub struct Settings {
     pub supported_assets: Vec<Asset>,
     // Vesu protocol API base URL
     pub vesu_api_base_url: String,
+    // Vesu API client settings
+    #[serde(default)]
+    pub vesu: VesuSettings,
     // Starknet RPC URL
     pub rpc_url: String,
     // Vault registry address
     pub vault_registry_address: String,
     // Database URL
     pub db_url: String,
+    // Signed request authentication settings
+    #[serde(default)]
+    pub auth: AuthSettings,
+    // Rate limiting settings, disabled when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitSettings>,
+    // Seconds to wait for in-flight requests and background work after SIGTERM/SIGINT
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shutdown_timeout_secs: Option<u64>,
+    // Readiness check settings
+    #[serde(default)]
+    pub readiness: ReadinessSettings,
+    // Outbound webhook settings, disabled when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhooks: Option<WebhookSettings>,
+    // Admin API settings, disabled when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin: Option<AdminSettings>,
+    // Date after which the unversioned route aliases of /v1 may be removed,
+    // sent in their Sunset header; defaults to 2027-04-17
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legacy_routes_sunset: Option<chrono::DateTime<chrono::Utc>>,
+    // Seconds the deposit aggregates of /stats are reused before querying the database again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats_cache_ttl_secs: Option<u64>,
+    // Interactive API reference served at /docs, disabled when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docs: Option<DocsSettings>,
 }
 
 impl Settings {
@@ -0,0 +1,38 @@
+use tokio::sync::watch;
+use tracing::info;
+
+/// Waits for SIGINT (Ctrl+C) or, on Unix, SIGTERM
+///
+/// # Panics
+///
+/// This function will panic if the signal handlers cannot be installed.
+pub async fn signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received SIGINT, shutting down"),
+        _ = terminate => info!("Received SIGTERM, shutting down"),
+    }
+}
+
+/// Waits until shutdown has been requested on the channel
+///
+/// Also returns if the sender is dropped, since no request can arrive anymore.
+pub async fn requested(receiver: &mut watch::Receiver<bool>) {
+    let _ = receiver.wait_for(|shutdown| *shutdown).await;
+}
@@ -0,0 +1,93 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use moka::future::Cache;
+
+use crate::{
+    orderbook::{
+        OrderbookProvider,
+        stats::{BucketTotals, DepositTotals},
+    },
+    primitives::StatsInterval,
+};
+
+/// Default time aggregates are reused before querying the database again, in seconds
+const DEFAULT_STATS_CACHE_TTL_SECS: u64 = 60;
+
+/// Maximum number of timeseries kept in the cache
+const STATS_TIMESERIES_CACHE_SIZE: u64 = 100;
+
+/// Aggregates read from the database and when they were read
+#[derive(Clone)]
+pub struct Snapshot<T> {
+    pub data: Arc<T>,
+    pub as_of: DateTime<Utc>,
+}
+
+impl<T> Snapshot<T> {
+    fn now(data: T) -> Self {
+        Self {
+            data: Arc::new(data),
+            as_of: Utc::now(),
+        }
+    }
+}
+
+/// Deposit aggregates behind `GET /stats` and `GET /stats/timeseries`
+///
+/// The aggregates scan the whole deposits table, so they are cached and only
+/// recomputed once expired. Concurrent requests for an expired entry share a
+/// single query. Prices are not cached here, so that the aggregates can be
+/// valued at the latest prices.
+pub struct DepositStatsProvider {
+    orderbook: Arc<OrderbookProvider>,
+    totals: Cache<(), Snapshot<DepositTotals>>,
+    timeseries: Cache<(StatsInterval, i64), Snapshot<Vec<BucketTotals>>>,
+}
+
+impl DepositStatsProvider {
+    /// Creates a new DepositStatsProvider instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `orderbook` - Orderbook holding the deposits
+    /// * `cache_ttl_secs` - How long aggregates are reused, defaults to 60 seconds
+    pub fn new(orderbook: Arc<OrderbookProvider>, cache_ttl_secs: Option<u64>) -> Self {
+        let ttl = Duration::from_secs(cache_ttl_secs.unwrap_or(DEFAULT_STATS_CACHE_TTL_SECS));
+
+        Self {
+            orderbook,
+            totals: Cache::builder().time_to_live(ttl).max_capacity(1).build(),
+            timeseries: Cache::builder()
+                .time_to_live(ttl)
+                .max_capacity(STATS_TIMESERIES_CACHE_SIZE)
+                .build(),
+        }
+    }
+
+    /// Returns the aggregates over all deposits
+    pub async fn totals(&self) -> Result<Snapshot<DepositTotals>, Arc<eyre::Error>> {
+        self.totals
+            .try_get_with((), async {
+                Ok(Snapshot::now(self.orderbook.get_deposit_totals().await?))
+            })
+            .await
+    }
+
+    /// Returns the aggregates of the `buckets` most recent buckets of `interval`
+    pub async fn timeseries(
+        &self,
+        interval: StatsInterval,
+        buckets: i64,
+    ) -> Result<Snapshot<Vec<BucketTotals>>, Arc<eyre::Error>> {
+        self.timeseries
+            .try_get_with((interval, buckets), async {
+                Ok(Snapshot::now(
+                    self.orderbook
+                        .get_deposit_timeseries(interval, buckets)
+                        .await?,
+                ))
+            })
+            .await
+    }
+}
@@ -0,0 +1,363 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use moka::future::Cache;
+use reqwest::{Client, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+use tracing::{Instrument, error, warn};
+
+use crate::{
+    metrics::{METRICS, UPSTREAM_VESU, upstream_span},
+    primitives::{ApiErrorCode, Response, VesuHistoryEntry, VesuPool, VesuPosition},
+};
+
+pub mod models;
+
+use models::{Envelope, convert_all};
+
+/// Default HTTP request timeout for Vesu API calls in seconds
+const VESU_REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Default number of retries after a failed attempt
+const VESU_MAX_RETRIES: u32 = 2;
+
+/// Delay before the first retry in milliseconds; doubled on every further retry
+const VESU_RETRY_BASE_DELAY_MS: u64 = 200;
+
+/// Default time a cached pools response is served without revalidation, in seconds
+const VESU_POOLS_FRESH_SECS: u64 = 30;
+
+/// Default time a cached pools response may be served while it is revalidated, in seconds
+const VESU_POOLS_STALE_SECS: u64 = 600;
+
+/// Maximum number of pools responses kept in the cache
+const VESU_POOLS_CACHE_SIZE: u64 = 1000;
+
+/// Errors returned by the Vesu API client
+#[derive(Error, Debug, Clone)]
+pub enum VesuError {
+    /// Vesu answered with a non-success status
+    #[error("Vesu responded with {0}")]
+    Status(StatusCode),
+
+    /// The request timed out
+    #[error("Vesu request timed out")]
+    Timeout,
+
+    /// The request could not be sent or the response could not be read
+    #[error("Vesu request failed: {0}")]
+    Request(String),
+
+    /// The response body is not in the expected format
+    #[error("Invalid Vesu response: {0}")]
+    InvalidResponse(String),
+}
+
+impl VesuError {
+    /// Whether the request may succeed if attempted again
+    fn is_retryable(&self) -> bool {
+        match self {
+            VesuError::Status(status) => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            VesuError::Timeout | VesuError::Request(_) => true,
+            VesuError::InvalidResponse(_) => false,
+        }
+    }
+}
+
+impl From<VesuError> for Response<()> {
+    /// Maps upstream failures to client-facing errors
+    ///
+    /// A 404 from Vesu is passed through, other client errors mean the request
+    /// parameters were rejected, timeouts become 504 and everything else 502.
+    fn from(error: VesuError) -> Self {
+        match error {
+            VesuError::Status(StatusCode::NOT_FOUND) => {
+                Response::error(ApiErrorCode::UpstreamVesuNotFound, "Not found on Vesu")
+            }
+            VesuError::Status(status)
+                if status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS =>
+            {
+                Response::error(
+                    ApiErrorCode::UpstreamVesuRejected,
+                    "Vesu rejected the request parameters",
+                )
+            }
+            VesuError::Timeout => {
+                Response::internal_error(ApiErrorCode::UpstreamVesuTimeout, error)
+            }
+            error => Response::internal_error(ApiErrorCode::UpstreamVesuFailed, error),
+        }
+    }
+}
+
+/// A cached response and when it was fetched
+#[derive(Clone)]
+struct CachedResponse {
+    data: Arc<Vec<VesuPool>>,
+    fetched_at: Instant,
+}
+
+/// Client for the Vesu API
+///
+/// Shares one connection pool across all requests, retries transient failures
+/// with exponential backoff and jitter, and caches pool data with
+/// stale-while-revalidate semantics: a fresh entry is served as-is, a stale
+/// entry is served while a single background request refreshes it, and an
+/// expired entry is fetched again before responding.
+pub struct VesuClient {
+    client: Client,
+    base_url: Url,
+    max_retries: u32,
+    pools_fresh_for: Duration,
+    pools: Cache<Option<String>, CachedResponse>,
+    revalidating: Mutex<HashSet<Option<String>>>,
+}
+
+impl VesuClient {
+    /// Creates a new VesuClient instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - Base URL of the Vesu API
+    /// * `timeout_secs` - Optional request timeout in seconds, defaults to 10
+    /// * `max_retries` - Optional number of retries after a failed attempt, defaults to 2
+    /// * `pools_fresh_secs` - Optional time pool data is served without
+    ///   revalidation, defaults to 30 seconds
+    /// * `pools_stale_secs` - Optional time pool data may be served while it is
+    ///   revalidated, defaults to 600 seconds
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the base URL is invalid or the HTTP client
+    /// fails to build.
+    pub fn new(
+        base_url: &str,
+        timeout_secs: Option<u64>,
+        max_retries: Option<u32>,
+        pools_fresh_secs: Option<u64>,
+        pools_stale_secs: Option<u64>,
+    ) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(
+                timeout_secs.unwrap_or(VESU_REQUEST_TIMEOUT_SECS),
+            ))
+            .build()
+            .expect("Failed to build http client");
+
+        let pools = Cache::builder()
+            .time_to_live(Duration::from_secs(
+                pools_stale_secs.unwrap_or(VESU_POOLS_STALE_SECS),
+            ))
+            .max_capacity(VESU_POOLS_CACHE_SIZE)
+            .build();
+
+        Self {
+            client,
+            base_url: Url::parse(base_url).expect("Invalid Vesu API base URL"),
+            max_retries: max_retries.unwrap_or(VESU_MAX_RETRIES),
+            pools_fresh_for: Duration::from_secs(pools_fresh_secs.unwrap_or(VESU_POOLS_FRESH_SECS)),
+            pools,
+            revalidating: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Fetches the positions of a wallet
+    pub async fn positions(&self, wallet_address: &str) -> Result<Vec<VesuPosition>, VesuError> {
+        let mut url = self.url(&["positions"]);
+        url.query_pairs_mut()
+            .append_pair("walletAddress", wallet_address);
+        let positions: Vec<models::Position> = self.get("positions", url).await?;
+        convert_all(positions)
+    }
+
+    /// Fetches the transaction history of a wallet
+    pub async fn history(&self, wallet_address: &str) -> Result<Vec<VesuHistoryEntry>, VesuError> {
+        let entries: Vec<models::HistoryEntry> = self
+            .get("history", self.url(&["users", wallet_address, "history"]))
+            .await?;
+        convert_all(entries)
+    }
+
+    /// Fetches all pools, or a single pool, serving cached data when possible
+    pub async fn pools(
+        self: &Arc<Self>,
+        pool_address: Option<&str>,
+    ) -> Result<Arc<Vec<VesuPool>>, VesuError> {
+        let key = pool_address.map(str::to_string);
+
+        if let Some(cached) = self.pools.get(&key).await {
+            if cached.fetched_at.elapsed() >= self.pools_fresh_for {
+                self.revalidate_pools(key);
+            }
+            return Ok(cached.data);
+        }
+
+        // Concurrent misses for the same key share a single request
+        self.pools
+            .try_get_with(key.clone(), self.fetch_pools(key))
+            .await
+            .map(|cached| cached.data)
+            .map_err(|e| (*e).clone())
+    }
+
+    /// Refreshes a cached pools response in the background
+    ///
+    /// Only one refresh per key runs at a time; the stale entry keeps being
+    /// served until it succeeds.
+    fn revalidate_pools(self: &Arc<Self>, key: Option<String>) {
+        if !self
+            .revalidating
+            .lock()
+            .expect("revalidating set poisoned")
+            .insert(key.clone())
+        {
+            return;
+        }
+
+        let client = Arc::clone(self);
+        tokio::spawn(async move {
+            match client.fetch_pools(key.clone()).await {
+                Ok(fresh) => client.pools.insert(key.clone(), fresh).await,
+                Err(e) => warn!(pool = ?key, "Failed to revalidate Vesu pools: {}", e),
+            }
+            client
+                .revalidating
+                .lock()
+                .expect("revalidating set poisoned")
+                .remove(&key);
+        });
+    }
+
+    async fn fetch_pools(&self, pool_address: Option<String>) -> Result<CachedResponse, VesuError> {
+        let url = match &pool_address {
+            Some(pool_address) => self.url(&["pools", pool_address]),
+            None => self.url(&["pools"]),
+        };
+        let data = match pool_address {
+            Some(_) => vec![self.get::<models::Pool>("pools", url).await?.try_into()?],
+            None => convert_all(self.get::<Vec<models::Pool>>("pools", url).await?)?,
+        };
+        Ok(CachedResponse {
+            data: Arc::new(data),
+            fetched_at: Instant::now(),
+        })
+    }
+
+    /// Builds a URL below the base URL, escaping every path segment
+    fn url(&self, segments: &[&str]) -> Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Vesu API base URL cannot be a base")
+            .pop_if_empty()
+            .extend(segments);
+        url
+    }
+
+    /// Sends a GET request, retrying transient failures, and returns the `data` field
+    async fn get<T: DeserializeOwned>(&self, operation: &str, url: Url) -> Result<T, VesuError> {
+        let mut attempt = 0;
+        loop {
+            match self.get_once(operation, url.clone()).await {
+                Err(e) if e.is_retryable() && attempt < self.max_retries => {
+                    attempt += 1;
+                    let delay = retry_delay(attempt);
+                    warn!(
+                        operation,
+                        attempt,
+                        delay_ms = delay.as_millis() as u64,
+                        "Retrying Vesu request: {}",
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn get_once<T: DeserializeOwned>(
+        &self,
+        operation: &str,
+        url: Url,
+    ) -> Result<T, VesuError> {
+        let started = Instant::now();
+        let result = self
+            .client
+            .get(url)
+            .send()
+            .instrument(upstream_span(UPSTREAM_VESU, operation))
+            .await;
+        let outcome = match &result {
+            Ok(response) if response.status().is_success() => "ok",
+            Ok(_) => "http_error",
+            Err(e) if e.is_timeout() => "timeout",
+            Err(_) => "error",
+        };
+        METRICS.observe_upstream(UPSTREAM_VESU, operation, outcome, started);
+
+        let response = result.map_err(|e| {
+            if e.is_timeout() {
+                VesuError::Timeout
+            } else {
+                VesuError::Request(e.to_string())
+            }
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            if status.is_server_error() {
+                let body = response.text().await.unwrap_or_default();
+                error!(operation, %status, body, "Vesu request failed");
+            }
+            return Err(VesuError::Status(status));
+        }
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| VesuError::Request(e.to_string()))?;
+        let envelope: Envelope<T> = serde_json::from_slice(&body).map_err(|e| {
+            error!(
+                operation,
+                "Vesu response does not match the expected schema: {}", e
+            );
+            VesuError::InvalidResponse(e.to_string())
+        })?;
+        Ok(envelope.data)
+    }
+}
+
+/// Computes the delay before a retry: exponential backoff with full jitter
+fn retry_delay(attempt: u32) -> Duration {
+    let base = VESU_RETRY_BASE_DELAY_MS.saturating_mul(1 << (attempt - 1).min(16));
+    Duration::from_millis(base + rand::random::<u64>() % base.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_escapes_path_segments() {
+        let client = VesuClient::new("https://api.vesu.xyz/", None, None, None, None);
+        assert_eq!(
+            client.url(&["users", "0x1/../admin", "history"]).as_str(),
+            "https://api.vesu.xyz/users/0x1%2F..%2Fadmin/history"
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_grows_with_jitter() {
+        for attempt in 1..=3 {
+            let base = VESU_RETRY_BASE_DELAY_MS << (attempt - 1);
+            let delay = retry_delay(attempt).as_millis() as u64;
+            assert!((base..2 * base).contains(&delay));
+        }
+    }
+}
@@ -0,0 +1,340 @@
+//! Response shapes of the Vesu API and their conversion into the OneSat schema.
+//!
+//! Vesu encodes numbers as `{ "value": "<integer>", "decimals": <n> }`; they are
+//! converted to decimals here so that handlers never see upstream formats.
+
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, num_bigint::BigInt};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::VesuError;
+use crate::primitives::{
+    VesuAssetStats, VesuHistoryEntry, VesuPool, VesuPoolAsset, VesuPosition, VesuPositionType,
+    VesuToken, VesuTokenAmount,
+};
+
+/// Envelope wrapping every Vesu API response
+#[derive(Deserialize)]
+pub struct Envelope<T> {
+    pub data: T,
+}
+
+/// A fixed-point number: `value` scaled down by `decimals`
+#[derive(Deserialize)]
+pub struct FixedPoint {
+    value: String,
+    decimals: u32,
+}
+
+impl FixedPoint {
+    fn to_decimal(&self) -> Result<BigDecimal, VesuError> {
+        let value = BigInt::from_str(&self.value).map_err(|e| {
+            VesuError::InvalidResponse(format!("invalid fixed-point value {}: {}", self.value, e))
+        })?;
+        Ok(BigDecimal::new(value, self.decimals as i64).normalized())
+    }
+}
+
+fn to_decimal(value: &Option<FixedPoint>) -> Result<Option<BigDecimal>, VesuError> {
+    value.as_ref().map(FixedPoint::to_decimal).transpose()
+}
+
+#[derive(Deserialize)]
+pub struct Token {
+    address: String,
+    name: String,
+    symbol: String,
+    decimals: u32,
+}
+
+impl From<Token> for VesuToken {
+    fn from(token: Token) -> Self {
+        VesuToken {
+            address: token.address,
+            name: token.name,
+            symbol: token.symbol,
+            decimals: token.decimals,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetStats {
+    total_supplied: Option<FixedPoint>,
+    total_debt: Option<FixedPoint>,
+    current_utilization: Option<FixedPoint>,
+    supply_apy: Option<FixedPoint>,
+    defi_spring_supply_apr: Option<FixedPoint>,
+    borrow_apr: Option<FixedPoint>,
+}
+
+impl TryFrom<AssetStats> for VesuAssetStats {
+    type Error = VesuError;
+
+    fn try_from(stats: AssetStats) -> Result<Self, Self::Error> {
+        Ok(VesuAssetStats {
+            total_supplied: to_decimal(&stats.total_supplied)?,
+            total_borrowed: to_decimal(&stats.total_debt)?,
+            utilization: to_decimal(&stats.current_utilization)?,
+            supply_apy: to_decimal(&stats.supply_apy)?,
+            reward_apr: to_decimal(&stats.defi_spring_supply_apr)?,
+            borrow_apr: to_decimal(&stats.borrow_apr)?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolAsset {
+    #[serde(flatten)]
+    token: Token,
+    v_token: Option<Token>,
+    usd_price: Option<FixedPoint>,
+    stats: Option<AssetStats>,
+}
+
+impl TryFrom<PoolAsset> for VesuPoolAsset {
+    type Error = VesuError;
+
+    fn try_from(asset: PoolAsset) -> Result<Self, Self::Error> {
+        Ok(VesuPoolAsset {
+            usd_price: to_decimal(&asset.usd_price)?,
+            stats: asset.stats.map(TryInto::try_into).transpose()?,
+            token: asset.token.into(),
+            v_token: asset.v_token.map(Into::into),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pool {
+    id: String,
+    address: Option<String>,
+    name: String,
+    #[serde(default)]
+    is_verified: bool,
+    #[serde(default)]
+    assets: Vec<PoolAsset>,
+}
+
+impl TryFrom<Pool> for VesuPool {
+    type Error = VesuError;
+
+    fn try_from(pool: Pool) -> Result<Self, Self::Error> {
+        Ok(VesuPool {
+            id: pool.id,
+            address: pool.address,
+            name: pool.name,
+            is_verified: pool.is_verified,
+            assets: pool
+                .assets
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// A token amount with an optional USD price, as used by positions and history
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenAmount {
+    #[serde(flatten)]
+    token: Token,
+    value: String,
+    usd_price: Option<FixedPoint>,
+}
+
+impl TryFrom<TokenAmount> for VesuTokenAmount {
+    type Error = VesuError;
+
+    fn try_from(amount: TokenAmount) -> Result<Self, Self::Error> {
+        let value = FixedPoint {
+            value: amount.value,
+            decimals: amount.token.decimals,
+        }
+        .to_decimal()?;
+        let usd_price = to_decimal(&amount.usd_price)?;
+        Ok(VesuTokenAmount {
+            usd_value: usd_price
+                .as_ref()
+                .map(|price| (&value * price).normalized()),
+            token: amount.token.into(),
+            amount: value,
+            usd_price,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PoolRef {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    #[serde(rename = "type")]
+    position_type: VesuPositionType,
+    pool: PoolRef,
+    collateral: TokenAmount,
+    collateral_shares: Option<TokenAmount>,
+    debt: Option<TokenAmount>,
+    #[serde(default)]
+    is_deprecated: bool,
+}
+
+impl TryFrom<Position> for VesuPosition {
+    type Error = VesuError;
+
+    fn try_from(position: Position) -> Result<Self, Self::Error> {
+        Ok(VesuPosition {
+            position_type: position.position_type,
+            pool_id: position.pool.id,
+            pool_name: position.pool.name,
+            collateral: position.collateral.try_into()?,
+            collateral_shares: position
+                .collateral_shares
+                .map(TryInto::try_into)
+                .transpose()?,
+            debt: position.debt.map(TryInto::try_into).transpose()?,
+            is_deprecated: position.is_deprecated,
+        })
+    }
+}
+
+/// Vesu reports timestamps either as unix seconds or as RFC 3339 strings
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Timestamp {
+    Unix(i64),
+    Rfc3339(DateTime<Utc>),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    #[serde(rename = "type")]
+    action: String,
+    tx_hash: String,
+    timestamp: Timestamp,
+    pool: Option<PoolRef>,
+    collateral: Option<TokenAmount>,
+    debt: Option<TokenAmount>,
+}
+
+impl TryFrom<HistoryEntry> for VesuHistoryEntry {
+    type Error = VesuError;
+
+    fn try_from(entry: HistoryEntry) -> Result<Self, Self::Error> {
+        let timestamp = match entry.timestamp {
+            Timestamp::Unix(secs) => DateTime::from_timestamp(secs, 0)
+                .ok_or_else(|| VesuError::InvalidResponse(format!("invalid timestamp {}", secs)))?,
+            Timestamp::Rfc3339(timestamp) => timestamp,
+        };
+        let (pool_id, pool_name) = match entry.pool {
+            Some(pool) => (Some(pool.id), Some(pool.name)),
+            None => (None, None),
+        };
+        Ok(VesuHistoryEntry {
+            action: entry.action,
+            tx_hash: entry.tx_hash,
+            timestamp,
+            pool_id,
+            pool_name,
+            collateral: entry.collateral.map(TryInto::try_into).transpose()?,
+            debt: entry.debt.map(TryInto::try_into).transpose()?,
+        })
+    }
+}
+
+/// Converts every upstream item, failing on the first invalid one
+pub fn convert_all<T, U>(items: Vec<T>) -> Result<Vec<U>, VesuError>
+where
+    T: TryInto<U, Error = VesuError>,
+{
+    items.into_iter().map(TryInto::try_into).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_pool_conversion() {
+        let pool: Pool = serde_json::from_value(json!({
+            "id": "0x1",
+            "name": "Prime",
+            "isVerified": true,
+            "protocolVersion": "v2",
+            "assets": [{
+                "address": "0xa",
+                "name": "Wrapped BTC",
+                "symbol": "WBTC",
+                "decimals": 8,
+                "vToken": { "address": "0xb", "name": "Vesu WBTC", "symbol": "vWBTC", "decimals": 18 },
+                "usdPrice": { "value": "65000000000000000000000", "decimals": 18 },
+                "stats": {
+                    "supplyApy": { "value": "52300000000000000", "decimals": 18 },
+                    "totalSupplied": { "value": "150000000", "decimals": 8 }
+                }
+            }]
+        }))
+        .unwrap();
+
+        let pool = VesuPool::try_from(pool).unwrap();
+        let asset = &pool.assets[0];
+        assert_eq!(asset.v_token.as_ref().unwrap().symbol, "vWBTC");
+        assert_eq!(asset.usd_price.as_ref().unwrap().to_string(), "65000");
+        let stats = asset.stats.as_ref().unwrap();
+        assert_eq!(stats.supply_apy.as_ref().unwrap().to_string(), "0.0523");
+        assert_eq!(stats.total_supplied.as_ref().unwrap().to_string(), "1.5");
+        assert!(stats.borrow_apr.is_none());
+    }
+
+    #[test]
+    fn test_position_valuation() {
+        let position: Position = serde_json::from_value(json!({
+            "type": "earn",
+            "pool": { "id": "0x1", "name": "Prime", "extensionContractAddress": "0x2" },
+            "collateral": {
+                "address": "0xa",
+                "name": "Wrapped BTC",
+                "symbol": "WBTC",
+                "decimals": 8,
+                "value": "50000000",
+                "usdPrice": { "value": "6500000", "decimals": 2 }
+            },
+            "walletAddress": "0x3"
+        }))
+        .unwrap();
+
+        let position = VesuPosition::try_from(position).unwrap();
+        assert_eq!(position.position_type, VesuPositionType::Earn);
+        assert_eq!(position.collateral.amount.to_string(), "0.5");
+        assert_eq!(
+            position.collateral.usd_value.as_ref().unwrap().to_string(),
+            "32500"
+        );
+        assert!(!position.is_deprecated);
+    }
+
+    #[test]
+    fn test_invalid_amount_is_rejected() {
+        let amount = FixedPoint {
+            value: "1.5".to_string(),
+            decimals: 18,
+        };
+        assert!(matches!(
+            amount.to_decimal(),
+            Err(VesuError::InvalidResponse(_))
+        ));
+    }
+}
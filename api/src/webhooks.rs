@@ -0,0 +1,373 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use eyre::Result;
+use futures_util::future::join_all;
+use hmac::{Hmac, Mac};
+use reqwest::{
+    Client, Url,
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect,
+};
+use sha2::Sha256;
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+use crate::{
+    orderbook::{OrderbookProvider, webhooks::PendingWebhookDelivery},
+    shutdown,
+};
+
+/// HTTP request timeout for webhook deliveries in seconds
+const WEBHOOK_REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Default interval between polls of the delivery queue in seconds
+const WEBHOOK_POLL_INTERVAL_SECS: u64 = 5;
+
+/// Default number of attempts before a delivery is moved to dead
+const WEBHOOK_MAX_ATTEMPTS: u32 = 10;
+
+/// Maximum number of deliveries sent per poll
+const WEBHOOK_BATCH_SIZE: i64 = 100;
+
+/// How long a claimed delivery is hidden from other dispatchers in seconds
+const WEBHOOK_LEASE_SECS: f64 = 60.0;
+
+/// Delay before the first retry in seconds; doubled on every further attempt
+const WEBHOOK_RETRY_BASE_SECS: u64 = 10;
+
+/// Upper bound of the retry delay in seconds
+const WEBHOOK_RETRY_MAX_SECS: u64 = 6 * 60 * 60;
+
+/// Header carrying the event name
+pub const EVENT_HEADER: &str = "x-onesat-event";
+
+/// Header carrying the delivery ID, stable across retries and replays
+pub const DELIVERY_HEADER: &str = "x-onesat-delivery";
+
+/// Header carrying the unix timestamp (seconds) included in the signature
+pub const TIMESTAMP_HEADER: &str = "x-onesat-timestamp";
+
+/// Header carrying `sha256=<hex HMAC-SHA256 of "<timestamp>.<body>">`
+pub const SIGNATURE_HEADER: &str = "x-onesat-signature";
+
+/// Delivers deposit lifecycle events to partner webhooks.
+///
+/// Deliveries are enqueued in Postgres by the `deposits_enqueue_webhook_deliveries`
+/// trigger, with a payload snapshotting the deposit at the transition. The
+/// dispatcher polls the queue, signs each payload with the subscription secret
+/// and POSTs it, retrying failures with exponential backoff until the maximum
+/// number of attempts is reached.
+pub struct WebhookDispatcher {
+    client: Client,
+    orderbook: Arc<OrderbookProvider>,
+    poll_interval_secs: u64,
+    max_attempts: u32,
+}
+
+impl WebhookDispatcher {
+    /// Creates a new WebhookDispatcher instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `orderbook` - Orderbook holding the delivery queue
+    /// * `poll_interval_secs` - Optional interval between queue polls in seconds.
+    ///   If None, uses the default value of 5 seconds.
+    /// * `max_attempts` - Optional number of attempts before giving up.
+    ///   If None, uses the default value of 10.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the HTTP client fails to build.
+    pub fn new(
+        orderbook: Arc<OrderbookProvider>,
+        poll_interval_secs: Option<u64>,
+        max_attempts: Option<u32>,
+    ) -> Self {
+        // Redirects are not followed, as they could point to a private address
+        let client = Client::builder()
+            .timeout(Duration::from_secs(WEBHOOK_REQUEST_TIMEOUT_SECS))
+            .redirect(redirect::Policy::none())
+            .dns_resolver(Arc::new(PublicResolver))
+            .build()
+            .expect("Failed to build http client");
+
+        Self {
+            client,
+            orderbook,
+            poll_interval_secs: poll_interval_secs.unwrap_or(WEBHOOK_POLL_INTERVAL_SECS),
+            max_attempts: max_attempts.unwrap_or(WEBHOOK_MAX_ATTEMPTS),
+        }
+    }
+
+    /// Starts the delivery loop.
+    ///
+    /// This method should be spawned in a separate task. It returns once shutdown
+    /// is requested on `shutdown`, after the current batch has been sent.
+    pub async fn start(&self, mut shutdown: watch::Receiver<bool>) {
+        info!("Starting webhook dispatcher");
+        loop {
+            match self.dispatch_due().await {
+                Ok(count) if count > 0 => info!(deliveries = count, "Dispatched webhooks"),
+                Ok(_) => {}
+                Err(e) => error!("Failed to dispatch webhooks: {}", e),
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(self.poll_interval_secs)) => {}
+                _ = shutdown::requested(&mut shutdown) => break,
+            }
+        }
+        info!("Stopped webhook dispatcher");
+    }
+
+    /// Claims and sends all due deliveries
+    async fn dispatch_due(&self) -> Result<usize> {
+        let deliveries = self
+            .orderbook
+            .claim_webhook_deliveries(WEBHOOK_BATCH_SIZE, WEBHOOK_LEASE_SECS)
+            .await?;
+        let count = deliveries.len();
+
+        join_all(deliveries.into_iter().map(|delivery| async move {
+            let delivery_id = delivery.delivery_id;
+            if let Err(e) = self.deliver(delivery).await {
+                error!(delivery_id, "Failed to record webhook delivery: {}", e);
+            }
+        }))
+        .await;
+
+        Ok(count)
+    }
+
+    /// Sends a single delivery and records the outcome
+    async fn deliver(&self, delivery: PendingWebhookDelivery) -> Result<()> {
+        let body = serde_json::to_vec(&delivery.payload)?;
+        let timestamp = chrono::Utc::now().timestamp();
+        let signature = sign_payload(&delivery.secret, timestamp, &body);
+
+        // Checked again, as the subscription may predate the checks on registration
+        let result = match parse_webhook_url(&delivery.url) {
+            Ok(url) => self
+                .client
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, &delivery.event)
+                .header(DELIVERY_HEADER, delivery.delivery_id.to_string())
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .header(SIGNATURE_HEADER, signature)
+                .body(body)
+                .send()
+                .await
+                .map_err(|e| format!("Request failed: {:#}", eyre::Report::new(e))),
+            Err(e) => Err(format!("Refused url: {}", e)),
+        };
+
+        let (error, status_code) = match result {
+            Ok(response) if response.status().is_success() => {
+                return self
+                    .orderbook
+                    .mark_webhook_delivered(delivery.delivery_id, response.status().as_u16() as i32)
+                    .await;
+            }
+            Ok(response) => (
+                format!("Endpoint responded with {}", response.status()),
+                Some(response.status().as_u16() as i32),
+            ),
+            Err(error) => (error, None),
+        };
+
+        let attempts = delivery.attempts as u32 + 1;
+        let next_attempt_at = (attempts < self.max_attempts).then(|| {
+            chrono::Utc::now() + chrono::Duration::seconds(retry_delay_secs(attempts) as i64)
+        });
+
+        warn!(
+            delivery_id = delivery.delivery_id,
+            attempts,
+            dead = next_attempt_at.is_none(),
+            "Webhook delivery failed: {}",
+            error
+        );
+
+        self.orderbook
+            .mark_webhook_failed(delivery.delivery_id, &error, status_code, next_attempt_at)
+            .await
+    }
+}
+
+/// Parses a webhook URL, accepting only HTTPS URLs whose host is a domain name
+///
+/// IP literals and local host names are refused. Domain names are checked when
+/// delivering, by [`PublicResolver`], since their addresses can change.
+pub fn parse_webhook_url(url: &str) -> Result<Url, String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid url: {}", e))?;
+    if url.scheme() != "https" {
+        return Err("Webhook url must use https".to_string());
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| "Webhook url must have a host".to_string())?;
+    if host.trim_matches(['[', ']']).parse::<IpAddr>().is_ok() {
+        return Err("Webhook url must use a domain name, not an IP address".to_string());
+    }
+    let domain = host.trim_end_matches('.').to_ascii_lowercase();
+    if domain == "localhost" || domain.ends_with(".localhost") || !domain.contains('.') {
+        return Err(format!("Webhook host must be a public domain: {}", domain));
+    }
+    Ok(url)
+}
+
+/// Resolves webhook hosts, keeping only public addresses
+///
+/// Connections are made to the addresses returned here, so a domain pointing
+/// to a loopback, private or link-local address cannot reach internal services,
+/// even if it changed since the subscription was registered.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect::<Vec<SocketAddr>>();
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether an address is publicly routable
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // 0.0.0.0/8 "this network"
+        || a == 0
+        // 100.64.0.0/10 shared address space
+        || (a == 100 && (64..128).contains(&b))
+        // 192.0.0.0/24 IETF protocol assignments
+        || (a == 192 && b == 0 && c == 0)
+        // 198.18.0.0/15 benchmarking
+        || (a == 198 && (18..20).contains(&b))
+        // 240.0.0.0/4 reserved
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let [first, second, ..] = ip.segments();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        || ip.is_unique_local()
+        || ip.is_unicast_link_local()
+        // 2001:db8::/32 documentation
+        || (first == 0x2001 && second == 0xdb8)
+        // ::/96 deprecated IPv4-compatible and 64:ff9b::/96 NAT64, which can embed private IPv4
+        || (first == 0 && second == 0)
+        || (first == 0x64 && second == 0xff9b))
+}
+
+/// Computes the delay before the next attempt after `attempts` failed attempts
+fn retry_delay_secs(attempts: u32) -> u64 {
+    WEBHOOK_RETRY_BASE_SECS
+        .saturating_mul(2u64.saturating_pow(attempts.saturating_sub(1)))
+        .min(WEBHOOK_RETRY_MAX_SECS)
+}
+
+/// Signs a payload as `sha256=<hex HMAC-SHA256 of "<timestamp>.<body>">`
+///
+/// Receivers recompute the HMAC over the timestamp header, a `.` and the raw
+/// request body with their subscription secret, and should reject stale
+/// timestamps to prevent replays.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_payload() {
+        // echo -n '1700000000.{"id":1}' | openssl dgst -sha256 -hmac secret
+        assert_eq!(
+            sign_payload("secret", 1_700_000_000, br#"{"id":1}"#),
+            "sha256=3dd1b9aef568d75f6790a84bd2e5dfa1f44409eef3cbdbd3f10b837376100c11"
+        );
+    }
+
+    #[test]
+    fn test_webhook_url_must_be_a_public_https_domain() {
+        assert!(parse_webhook_url("https://partner.example/onesat").is_ok());
+        for url in [
+            "http://partner.example/onesat",
+            "https://10.0.0.5/",
+            "https://127.0.0.1/",
+            "https://[::1]/",
+            "https://169.254.169.254/latest/meta-data",
+            "https://localhost/",
+            "https://api.localhost/",
+            "https://intranet/",
+            "not a url",
+        ] {
+            assert!(parse_webhook_url(url).is_err(), "{} accepted", url);
+        }
+    }
+
+    #[test]
+    fn test_only_public_ips_are_resolved() {
+        for ip in ["93.184.216.34", "2606:2800:220:1:248:1893:25c8:1946"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{} refused", ip);
+        }
+        for ip in [
+            "10.0.0.5",
+            "127.0.0.1",
+            "169.254.169.254",
+            "172.16.0.1",
+            "192.168.1.1",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fe80::1",
+            "fd00::1",
+            "::ffff:10.0.0.5",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{} accepted", ip);
+        }
+    }
+
+    #[test]
+    fn test_retry_delay_is_exponential_and_capped() {
+        assert_eq!(retry_delay_secs(1), 10);
+        assert_eq!(retry_delay_secs(2), 20);
+        assert_eq!(retry_delay_secs(3), 40);
+        assert_eq!(retry_delay_secs(30), WEBHOOK_RETRY_MAX_SECS);
+    }
+}
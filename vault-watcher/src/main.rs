@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use starknet::{
     accounts::{ExecutionEncoding, SingleOwnerAccount},
@@ -6,6 +6,8 @@ use starknet::{
     providers::{JsonRpcClient, Provider, Url, jsonrpc::HttpTransport},
     signers::{LocalWallet, SigningKey},
 };
+use tokio::sync::watch;
+use tracing::{info, warn};
 
 use crate::{
     orderbook::OrderbookProvider,
@@ -16,10 +18,14 @@ use crate::{
 mod orderbook;
 mod primitives;
 mod settings;
+mod shutdown;
 mod watcher;
 
 const SETTINGS_FILE: &str = "settings.json";
 
+/// Default time allowed for the deposit in progress to finish on shutdown
+const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 60;
+
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt().init();
@@ -63,5 +69,28 @@ async fn main() {
         settings.polling_interval,
     );
 
-    watcher.start().await;
+    let (shutdown_sender, shutdown) = watch::channel(false);
+    tokio::spawn(async move {
+        shutdown::signal().await;
+        let _ = shutdown_sender.send(true);
+    });
+
+    let shutdown_timeout = Duration::from_secs(
+        settings
+            .shutdown_timeout_secs
+            .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_SECS),
+    );
+    let mut deadline_shutdown = shutdown.clone();
+    let deadline = async move {
+        shutdown::requested(&mut deadline_shutdown).await;
+        tokio::time::sleep(shutdown_timeout).await;
+    };
+
+    tokio::select! {
+        _ = watcher.start(shutdown) => info!("Shutdown complete"),
+        _ = deadline => warn!(
+            timeout_secs = shutdown_timeout.as_secs(),
+            "Shutdown deadline elapsed while processing a deposit, it may need to be reconciled"
+        ),
+    }
 }
@@ -24,7 +24,7 @@ impl OrderbookProvider {
     /// Retrieves all deposits with a specific status
     ///
     /// # Arguments
-    /// * `status` - The status to filter by ("created", "initiated", or "deposited")
+    /// * `status` - The status to filter by ("created", "initiated", "deposited" or "cancelled")
     ///
     /// # Returns
     /// A list of deposits matching the status
@@ -40,7 +40,8 @@ impl OrderbookProvider {
                 target_address,
                 deposit_address,
                 status,
-                created_at
+                created_at,
+                request_id
             FROM deposits
             WHERE status = $1
             ORDER BY created_at DESC
@@ -53,6 +54,68 @@ impl OrderbookProvider {
         Ok(deposits)
     }
 
+    /// Retrieves the deposits cancelled since a given time whose address has not
+    /// been reported as funded yet
+    ///
+    /// # Arguments
+    /// * `since` - Only deposits cancelled at or after this time are returned
+    ///
+    /// # Returns
+    /// A list of cancelled deposits, most recently cancelled first
+    pub async fn get_unalerted_cancelled_deposits(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<DepositResponse>> {
+        let deposits = sqlx::query_as::<_, DepositResponse>(
+            r#"
+            SELECT
+                deposit_id,
+                user_address,
+                action,
+                amount,
+                token,
+                target_address,
+                deposit_address,
+                status,
+                created_at,
+                request_id
+            FROM deposits
+            WHERE status = 'cancelled'
+                AND cancelled_at >= $1
+                AND cancelled_funds_alerted_at IS NULL
+            ORDER BY cancelled_at DESC
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deposits)
+    }
+
+    /// Records that funds at the address of a cancelled deposit were reported,
+    /// so that they are reported only once
+    ///
+    /// # Arguments
+    /// * `deposit_id` - The cancelled deposit ID
+    ///
+    /// # Returns
+    /// Result indicating success or failure
+    pub async fn mark_cancelled_funds_alerted(&self, deposit_id: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE deposits
+            SET cancelled_funds_alerted_at = NOW()
+            WHERE deposit_id = $1
+            "#,
+        )
+        .bind(deposit_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Updates the status of a deposit
     ///
     /// # Arguments
@@ -103,26 +166,29 @@ impl OrderbookProvider {
         Ok(())
     }
 
-    /// Updates both the status and transaction hash for a deposit
+    /// Updates both the status and transaction hash of a deposit that is still created
+    ///
+    /// The status is only written while the deposit is `created`, so that a
+    /// deposit cancelled by its owner meanwhile stays cancelled.
     ///
     /// # Arguments
     /// * `deposit_id` - The deposit ID to update
-    /// * `new_status` - The new status to set ("created", "initiated", or "deposited")
+    /// * `new_status` - The new status to set ("initiated" or "deposited")
     /// * `tx_hash` - The transaction hash to set
     ///
     /// # Returns
-    /// Result indicating success or failure
+    /// `false` if the deposit was no longer created, e.g. cancelled meanwhile
     pub async fn update_deposit_status_and_tx_hash(
         &self,
         deposit_id: &str,
         new_status: &str,
         tx_hash: &str,
-    ) -> Result<()> {
-        sqlx::query(
+    ) -> Result<bool> {
+        let result = sqlx::query(
             r#"
             UPDATE deposits
             SET status = $1, deposit_tx_hash = $2
-            WHERE deposit_id = $3
+            WHERE deposit_id = $3 AND status = 'created'
             "#,
         )
         .bind(new_status)
@@ -131,6 +197,6 @@ impl OrderbookProvider {
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 }
@@ -12,6 +12,8 @@ pub enum DepositStatus {
     Initiated,
     /// Deposit has been confirmed on-chain
     Deposited,
+    /// Deposit was abandoned by its owner before being funded
+    Cancelled,
 }
 
 impl std::fmt::Display for DepositStatus {
@@ -20,6 +22,7 @@ impl std::fmt::Display for DepositStatus {
             DepositStatus::Created => write!(f, "created"),
             DepositStatus::Initiated => write!(f, "initiated"),
             DepositStatus::Deposited => write!(f, "deposited"),
+            DepositStatus::Cancelled => write!(f, "cancelled"),
         }
     }
 }
@@ -48,6 +51,8 @@ pub struct DepositResponse {
     /// Timestamp when deposit was created
     #[sqlx(rename = "created_at")]
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// ID of the API request that created the deposit
+    pub request_id: Option<String>,
 }
 
 /// Custom serializer for BigDecimal to ensure it's serialized as a plain string
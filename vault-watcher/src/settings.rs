@@ -8,6 +8,8 @@ pub struct Settings {
     pub vault_registry_address: String,
     pub private_key: String,
     pub account_address: String,
+    /// Seconds to wait for the deposit in progress to finish after SIGTERM/SIGINT
+    pub shutdown_timeout_secs: Option<u64>,
 }
 
 impl Settings {
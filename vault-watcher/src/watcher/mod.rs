@@ -4,7 +4,8 @@ use starknet::{
     core::types::Felt,
     providers::{JsonRpcClient, jsonrpc::HttpTransport},
 };
-use tracing::{error, info, warn};
+use tokio::sync::watch;
+use tracing::{error, info, instrument, warn};
 mod erc20;
 mod errors;
 pub mod registry;
@@ -12,6 +13,7 @@ pub mod registry;
 use crate::{
     orderbook::OrderbookProvider,
     primitives::DepositResponse,
+    shutdown,
     watcher::{erc20::ERC20, registry::VaultRegistry},
 };
 
@@ -24,6 +26,12 @@ const DEPOSIT_STATUS_CREATED: &str = "created";
 /// Deposit status after vault is deployed
 const DEPOSIT_STATUS_DEPOSITED: &str = "deposited";
 
+/// Number of polling cycles between balance checks of cancelled deposits
+const CANCELLED_CHECK_INTERVAL_CYCLES: u64 = 30;
+
+/// Deposits cancelled more than this many days ago are no longer checked for funds
+const CANCELLED_WATCH_DAYS: i64 = 30;
+
 /// Default chain ID for vault deployment
 const DEFAULT_ACTION: u128 = 1;
 
@@ -59,10 +67,18 @@ impl VaultWatcher {
 
     /// Starts the vault watcher main loop
     ///
-    /// Continuously polls for new deposits and processes them
-    pub async fn start(&self) {
+    /// Continuously polls for new deposits and processes them until shutdown is
+    /// requested on `shutdown`. A deposit that is being processed when shutdown
+    /// is requested is finished, so a deployed vault always has its transaction
+    /// hash persisted.
+    ///
+    /// Cancelled deposits are never deployed. Every
+    /// `CANCELLED_CHECK_INTERVAL_CYCLES` cycles their addresses are checked for
+    /// funds that arrived after cancellation.
+    pub async fn start(&self, mut shutdown: watch::Receiver<bool>) {
+        let mut cycle: u64 = 0;
         loop {
-            match self.process_pending_deposits().await {
+            match self.process_pending_deposits(&shutdown).await {
                 Ok(count) => {
                     if count > 0 {
                         info!(processed = count, "Successfully processed deposits");
@@ -73,12 +89,28 @@ impl VaultWatcher {
                 }
             }
 
-            tokio::time::sleep(Duration::from_secs(self.polling_interval)).await;
+            if cycle % CANCELLED_CHECK_INTERVAL_CYCLES == 0
+                && let Err(e) = self.check_cancelled_deposits().await
+            {
+                error!(error = ?e, "Error checking cancelled deposits");
+            }
+            cycle += 1;
+
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(self.polling_interval)) => {}
+                _ = shutdown::requested(&mut shutdown) => break,
+            }
         }
+        info!("Vault watcher stopped");
     }
 
     /// Fetches and processes all pending deposits
-    async fn process_pending_deposits(&self) -> Result<usize, Box<dyn std::error::Error>> {
+    ///
+    /// Stops before the next deposit once shutdown is requested.
+    async fn process_pending_deposits(
+        &self,
+        shutdown: &watch::Receiver<bool>,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
         let deposits = self
             .orderbook
             .get_deposits_by_status(DEPOSIT_STATUS_CREATED)
@@ -92,9 +124,14 @@ impl VaultWatcher {
 
         let mut processed = 0;
         for deposit in deposits {
+            if *shutdown.borrow() {
+                info!("Shutdown requested, leaving remaining deposits for the next run");
+                break;
+            }
             if let Err(e) = self.process_deposit(&deposit).await {
                 error!(
                     deposit_id = %deposit.deposit_id,
+                    request_id = deposit.request_id.as_deref(),
                     error = ?e,
                     "Failed to process deposit"
                 );
@@ -107,6 +144,16 @@ impl VaultWatcher {
     }
 
     /// Processes a single deposit by verifying balance and deploying vault
+    ///
+    /// Runs in a span carrying the ID of the API request that created the
+    /// deposit, so its logs can be correlated with the API's.
+    #[instrument(
+        skip_all,
+        fields(
+            deposit_id = %deposit.deposit_id,
+            request_id = deposit.request_id.as_deref(),
+        )
+    )]
     async fn process_deposit(
         &self,
         deposit: &DepositResponse,
@@ -124,6 +171,74 @@ impl VaultWatcher {
         Ok(())
     }
 
+    /// Alerts once for every recently cancelled deposit whose address holds funds
+    ///
+    /// Funds sent to a cancelled deposit address are not deployed into the vault
+    /// and have to be returned by an operator. Deposits are watched for
+    /// `CANCELLED_WATCH_DAYS` after their cancellation, and the alert is recorded
+    /// in the database so it is not repeated, even after a restart. A deposit
+    /// that cannot be checked is logged and skipped.
+    async fn check_cancelled_deposits(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let since = chrono::Utc::now() - chrono::Duration::days(CANCELLED_WATCH_DAYS);
+        let deposits = self
+            .orderbook
+            .get_unalerted_cancelled_deposits(since)
+            .await?;
+
+        for deposit in &deposits {
+            let (token_address, deposit_address) = match (
+                Self::parse_address(&deposit.token, "token"),
+                Self::parse_address(&deposit.deposit_address, "deposit"),
+            ) {
+                (Ok(token_address), Ok(deposit_address)) => (token_address, deposit_address),
+                (Err(e), _) | (_, Err(e)) => {
+                    warn!(
+                        deposit_id = %deposit.deposit_id,
+                        error = ?e,
+                        "Skipping cancelled deposit with an invalid address"
+                    );
+                    continue;
+                }
+            };
+            let erc20 = erc20::StarknetERC20::new(token_address, self.provider.clone());
+            let balance = match erc20.balance_of(deposit_address).await {
+                Ok(balance) => balance,
+                Err(e) => {
+                    warn!(
+                        deposit_id = %deposit.deposit_id,
+                        error = ?e,
+                        "Failed to check balance of cancelled deposit"
+                    );
+                    continue;
+                }
+            };
+
+            if balance.low() != 0 || balance.high() != 0 {
+                error!(
+                    deposit_id = %deposit.deposit_id,
+                    user = %deposit.user_address,
+                    deposit_address = %deposit.deposit_address,
+                    token = %deposit.token,
+                    balance = %balance,
+                    "Funds arrived at a cancelled deposit address"
+                );
+                if let Err(e) = self
+                    .orderbook
+                    .mark_cancelled_funds_alerted(&deposit.deposit_id)
+                    .await
+                {
+                    error!(
+                        deposit_id = %deposit.deposit_id,
+                        error = ?e,
+                        "Failed to record alert for cancelled deposit"
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Verifies that the deposit address has sufficient balance
     async fn verify_deposit_balance(
         &self,
@@ -192,7 +307,8 @@ impl VaultWatcher {
         );
 
         // Update deposit status to 'deposited' and save the transaction hash
-        self.orderbook
+        let updated = self
+            .orderbook
             .update_deposit_status_and_tx_hash(
                 &deposit.deposit_id,
                 DEPOSIT_STATUS_DEPOSITED,
@@ -206,6 +322,15 @@ impl VaultWatcher {
                 )
             })?;
 
+        if !updated {
+            warn!(
+                deposit_id = %deposit.deposit_id,
+                tx_hash = %tx_hash,
+                "Deposit was cancelled meanwhile, leaving its status unchanged"
+            );
+            return Ok(());
+        }
+
         info!(
             deposit_id = %deposit.deposit_id,
             status = DEPOSIT_STATUS_DEPOSITED,